@@ -0,0 +1,114 @@
+use crate::item::Item;
+
+/// A group of items that can be searched by tag.
+#[derive(Clone, Default)]
+pub struct ItemCollection {
+    items: Vec<Item>,
+}
+
+impl ItemCollection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    pub fn add(&mut self, item: Item) {
+        self.items.push(item);
+    }
+    
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+    
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+    
+    pub fn iter(&self) -> std::slice::Iter<'_, Item> {
+        self.items.iter()
+    }
+    
+    /// The items with a tag matching `value` case-insensitively; see `Tag::value_matches`.
+    /// Deleted items are skipped unless `include_deleted` is set.
+    pub fn find_by_tag_value(&self, value: &str, include_deleted: bool) -> Vec<&Item> {
+        self.items
+            .iter()
+            .filter(|item| include_deleted || !item.is_deleted())
+            .filter(|item| item.find_tag_by_value_ci(value).is_some())
+            .collect()
+    }
+    
+    /// The items carrying the tag with the given id. Deleted items are skipped unless
+    /// `include_deleted` is set.
+    pub fn find_by_tag_id(&self, tag_id: &str, include_deleted: bool) -> Vec<&Item> {
+        self.items
+            .iter()
+            .filter(|item| include_deleted || !item.is_deleted())
+            .filter(|item| item.has_tag(tag_id))
+            .collect()
+    }
+}
+
+impl From<Vec<Item>> for ItemCollection {
+    fn from(items: Vec<Item>) -> Self {
+        Self { items }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::FileType;
+    use crate::tag::Tag;
+    
+    fn create_item(tags: &[&Tag]) -> Item {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image).unwrap();
+        for tag in tags {
+            item.add_tag((*tag).clone()).unwrap();
+        }
+        
+        item
+    }
+    
+    fn ids(items: Vec<&Item>) -> Vec<&str> {
+        items.into_iter().map(|item| item.get_id()).collect()
+    }
+    
+    #[test]
+    fn test_item_collection_find_by_tag() {
+        let cats = Tag::new(String::from("Cats"));
+        let dogs = Tag::new(String::from("Dogs"));
+        let beach = Tag::new(String::from("Beach"));
+        
+        let first = create_item(&[&cats, &beach]);
+        let second = create_item(&[&cats, &dogs]);
+        let third = create_item(&[&dogs]);
+        let collection = ItemCollection::from(vec![first.clone(), second.clone(), third.clone()]);
+        assert_eq!(collection.len(), 3);
+        
+        assert_eq!(ids(collection.find_by_tag_value("cats", false)), vec![first.get_id(), second.get_id()]);
+        assert_eq!(ids(collection.find_by_tag_value(" DOGS ", false)), vec![second.get_id(), third.get_id()]);
+        assert!(collection.find_by_tag_value("Birds", false).is_empty());
+        
+        assert_eq!(ids(collection.find_by_tag_id(beach.get_id(), false)), vec![first.get_id()]);
+        assert_eq!(ids(collection.find_by_tag_id(dogs.get_id(), false)), vec![second.get_id(), third.get_id()]);
+    }
+    
+    #[test]
+    fn test_item_collection_skips_deleted_items() {
+        let cats = Tag::new(String::from("Cats"));
+        
+        let mut deleted = create_item(&[&cats]);
+        deleted.delete(None).unwrap();
+        let kept = create_item(&[&cats]);
+        
+        let mut collection = ItemCollection::new();
+        assert!(collection.is_empty());
+        collection.add(deleted.clone());
+        collection.add(kept.clone());
+        
+        assert_eq!(ids(collection.find_by_tag_value("Cats", false)), vec![kept.get_id()]);
+        assert_eq!(ids(collection.find_by_tag_value("Cats", true)), vec![deleted.get_id(), kept.get_id()]);
+        assert_eq!(ids(collection.find_by_tag_id(cats.get_id(), false)), vec![kept.get_id()]);
+        assert_eq!(ids(collection.find_by_tag_id(cats.get_id(), true)).len(), 2);
+    }
+}
\ No newline at end of file
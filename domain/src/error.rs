@@ -0,0 +1,85 @@
+use crate::file_name::FileNameError;
+use crate::instance::InstanceError;
+use crate::item::ItemError;
+use crate::tag::TagError;
+use crate::version::VersionError;
+
+/// Wraps the error types of every module so results from different modules can share one error type.
+#[derive(Debug, thiserror::Error)]
+pub enum TerferError {
+    #[error("Version error: {0}")]
+    Version(#[from] VersionError),
+    #[error("Instance error: {0}")]
+    Instance(#[from] InstanceError),
+    #[error("Item error: {0}")]
+    Item(#[from] ItemError),
+    #[error("Tag error: {0}")]
+    Tag(#[from] TagError),
+    #[error("File name error: {0}")]
+    FileName(#[from] FileNameError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_name::FileName;
+    use crate::instance::InstanceType;
+    use crate::item::{FileType, Item};
+    use crate::tag::Tag;
+    use crate::version::{Version, VersionLevel};
+    
+    fn parse_version(version: &str) -> Result<Version, TerferError> {
+        Ok(Version::from_string(version)?)
+    }
+    
+    fn parse_instance_type(instance_type: &str) -> Result<InstanceType, TerferError> {
+        Ok(instance_type.parse::<InstanceType>()?)
+    }
+    
+    fn edit_item(item: &mut Item, hash: &str) -> Result<(), TerferError> {
+        item.edit_with_hash(String::from("Edited"), VersionLevel::Patch, Some(String::from(hash)))?;
+        Ok(())
+    }
+    
+    fn set_tag_color(tag: &mut Tag, color: &str) -> Result<(), TerferError> {
+        tag.set_color(String::from(color), String::from("Color changed"))?;
+        Ok(())
+    }
+    
+    fn parse_file_name(file_name: &str) -> Result<FileName, TerferError> {
+        Ok(FileName::from_string(file_name)?)
+    }
+    
+    #[test]
+    fn test_version_error_into_terfer_error() {
+        let result = parse_version("not.a.version");
+        assert!(matches!(result, Err(TerferError::Version(_))));
+    }
+    
+    #[test]
+    fn test_instance_error_into_terfer_error() {
+        let result = parse_instance_type("Exploded");
+        assert!(matches!(result, Err(TerferError::Instance(InstanceError::InvalidInstanceType(_)))));
+    }
+    
+    #[test]
+    fn test_item_error_into_terfer_error() {
+        let mut item = Item::new(String::from("res/files"), String::from("md"), FileType::MarkdownNote).unwrap();
+        let result = edit_item(&mut item, "not a hash");
+        assert!(matches!(result, Err(TerferError::Item(ItemError::InvalidHash(_)))));
+    }
+    
+    #[test]
+    fn test_tag_error_into_terfer_error() {
+        let mut tag = Tag::new(String::from("Tag"));
+        let result = set_tag_color(&mut tag, "red");
+        assert!(matches!(result, Err(TerferError::Tag(TagError::InvalidColor(_)))));
+    }
+    
+    #[test]
+    fn test_file_name_error_into_terfer_error() {
+        let result = parse_file_name("no-separator");
+        assert!(matches!(result, Err(TerferError::FileName(_))));
+        assert!(result.unwrap_err().to_string().starts_with("File name error: "));
+    }
+}
\ No newline at end of file
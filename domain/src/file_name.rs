@@ -1,26 +1,47 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::str::FromStr;
 use jiff::fmt::strtime::format;
 use jiff::Zoned;
-use crate::version::Version;
+use crate::version::{Version, VersionError};
 
 const FILE_NAME_DATETIME_FORMAT: &'static str = "%Y-%m-%d-%H-%M-%S-%f%z";
 const FILE_NAME_PLUS_REPLACEMENT: &'static str = "-PLUS-";
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileName {
     datetime: Zoned,
     version: Version,
 }
 
 impl FileName {
+    /// Parses a file name produced by `try_to_string`. The parsed datetime has the same instant
+    /// and UTC offset as the original, but the time zone name isn't stored, so it is
+    /// represented as a fixed offset zone.
     pub fn from_string(file_name: &str) -> Result<Self, FileNameError> {
-        let parts: Vec<&str> = file_name.split('_').collect();
-        if parts.len() != 2 {
-            return Err(FileNameError::FilenameError(format!("Too many parts in filename: {}", file_name.to_string())));
+        Self::from_string_with_format(file_name, FILE_NAME_DATETIME_FORMAT)
+    }
+    
+    /// Parses a file name whose datetime was written with a custom `strftime` format; see
+    /// `to_string_with_format`. The version is split off at the last `_`, since versions never
+    /// contain one, and each field is checked before it is parsed.
+    pub fn from_string_with_format(file_name: &str, datetime_format: &str) -> Result<Self, FileNameError> {
+        let (datetime, version) = match file_name.rsplit_once('_') {
+            Some(parts) => parts,
+            None => return Err(FileNameError::FilenameError(format!("Missing '_' separator in filename: {}", file_name))),
+        };
+        
+        if datetime.is_empty() || !datetime.is_ascii() {
+            return Err(FileNameError::FilenameError(format!("Invalid datetime in filename: {}", file_name)));
         }
         
-        let file_name = parts[0].replace(FILE_NAME_PLUS_REPLACEMENT, "+");
-        let datetime = Zoned::strptime(FILE_NAME_DATETIME_FORMAT, file_name)?;
-        let version = Version::from_string(parts[1]).unwrap();
+        if version.is_empty() || !version.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.') {
+            return Err(FileNameError::FilenameError(format!("Invalid version in filename: {}", file_name)));
+        }
+        
+        let datetime = Zoned::strptime(datetime_format, datetime.replace(FILE_NAME_PLUS_REPLACEMENT, "+"))?;
+        let version = Version::from_string(version)?;
         
         Ok(Self {
             datetime,
@@ -35,6 +56,15 @@ impl FileName {
         }
     }
     
+    /// Like `new`, but stamps the current time in UTC rather than the system time zone, so
+    /// names written on different machines use the same `+0000` offset.
+    pub fn new_utc(version: Version) -> Self {
+        Self {
+            datetime: Zoned::now().with_time_zone(jiff::tz::TimeZone::UTC),
+            version,
+        }
+    }
+    
     pub fn get_version(&self) -> &Version {
         &self.version
     }
@@ -43,35 +73,71 @@ impl FileName {
         &self.datetime
     }
     
-    pub fn to_string(&self) -> Result<String, FileNameError> {
-        let datetime = format(FILE_NAME_DATETIME_FORMAT, &self.datetime)?.replace("+", FILE_NAME_PLUS_REPLACEMENT);
+    pub fn try_to_string(&self) -> Result<String, FileNameError> {
+        self.to_string_with_format(FILE_NAME_DATETIME_FORMAT)
+    }
+    
+    /// Formats the file name with a custom `strftime` datetime format. The format should
+    /// include an offset (`%z`) so the result can be parsed back.
+    pub fn to_string_with_format(&self, datetime_format: &str) -> Result<String, FileNameError> {
+        let datetime = format(datetime_format, &self.datetime)?.replace("+", FILE_NAME_PLUS_REPLACEMENT);
         Ok(format!("{}_{}", datetime, self.version.file_safe_string()))
     }
+    
+    /// Compares the version and the datetime truncated to whole seconds, so names created
+    /// within the same second match.
+    pub fn eq_ignoring_subsecond(&self, other: &FileName) -> bool {
+        self.version == other.version
+            && self.datetime.timestamp().as_second() == other.datetime.timestamp().as_second()
+    }
 }
 
-#[derive(Debug)]
-pub enum FileNameError {
-    FileUrlDateTime(jiff::Error),
-    FilenameError(String),
+/// Returns the index pairs `(i, j)`, with `i < j`, of names that serialize to the same
+/// string and would overwrite each other on disk. Names that fail to serialize are skipped.
+pub fn find_collisions(names: &[FileName]) -> Vec<(usize, usize)> {
+    let mut seen: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut collisions = Vec::new();
+    
+    for (index, name) in names.iter().enumerate() {
+        let serialized = match name.try_to_string() {
+            Ok(serialized) => serialized,
+            Err(_) => continue,
+        };
+        
+        let earlier = seen.entry(serialized).or_default();
+        collisions.extend(earlier.iter().map(|&first| (first, index)));
+        earlier.push(index);
+    }
+    
+    collisions
 }
 
-impl From<jiff::Error> for FileNameError {
-    fn from(e: jiff::Error) -> Self {
-        FileNameError::FileUrlDateTime(e)
+impl Display for FileName {
+    /// Writes the default format, which can't fail for a zoned datetime.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let file_name = self.try_to_string().map_err(|_| std::fmt::Error)?;
+        f.write_str(&file_name)
     }
 }
 
-impl std::error::Error for FileNameError {}
-
-impl std::fmt::Display for FileNameError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            FileNameError::FileUrlDateTime(e) => write!(f, "File URL DateTime Error: {}", e),
-            FileNameError::FilenameError(e) => write!(f, "Filename Error: {}", e),
-        }
+impl FromStr for FileName {
+    type Err = FileNameError;
+    
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_string(s)
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum FileNameError {
+    #[error("File URL DateTime Error: {0}")]
+    FileUrlDateTime(#[from] jiff::Error),
+    #[error("Filename Error: {0}")]
+    FilenameError(String),
+    #[error("Filename Version Error: {0}")]
+    Version(#[from] VersionError),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,6 +153,191 @@ mod tests {
     #[test]
     fn test_file_name_to_string() {
         let file_name = FileName::new(Version::new(1, 2, 3));
-        assert_eq!(file_name.to_string().unwrap(), format!("{}_{}", file_name.get_datetime().strftime(FILE_NAME_DATETIME_FORMAT).to_string(), file_name.get_version().file_safe_string()));
+        let datetime = file_name.get_datetime().strftime(FILE_NAME_DATETIME_FORMAT).to_string().replace("+", FILE_NAME_PLUS_REPLACEMENT);
+        assert_eq!(file_name.try_to_string().unwrap(), format!("{}_{}", datetime, file_name.get_version().file_safe_string()));
+        assert_eq!(file_name.to_string(), file_name.try_to_string().unwrap());
+    }
+    
+    #[test]
+    fn test_file_name_separator() {
+        let result = FileName::from_string("2024-07-30-00-56-25-031870928-0600");
+        assert!(matches!(result, Err(FileNameError::FilenameError(ref e)) if e.starts_with("Missing '_' separator")));
+        
+        assert!(FileName::from_string("2024-07-30-00-56-25-031870928-0600_1-2-3").is_ok());
+        
+        let result = FileName::from_string("2024-07-30-00-56-25-031870928-0600_1-2-3_extra");
+        assert!(matches!(result, Err(FileNameError::FileUrlDateTime(_))));
+        
+        let result = FileName::from_string("_1-2-3");
+        assert!(matches!(result, Err(FileNameError::FilenameError(ref e)) if e.starts_with("Invalid datetime")));
+        
+        let result = FileName::from_string("2024-07-30-00-56-25-031870928-0600_");
+        assert!(matches!(result, Err(FileNameError::FilenameError(ref e)) if e.starts_with("Invalid version")));
+        
+        let result = FileName::from_string("2024-07-30-00-56-25-031870928-0600_1-2-3/..");
+        assert!(matches!(result, Err(FileNameError::FilenameError(ref e)) if e.starts_with("Invalid version")));
+    }
+    
+    #[test]
+    fn test_file_name_invalid_version() {
+        let result = FileName::from_string("2024-07-30-00-56-25-031870928-0600_abc");
+        assert!(matches!(result, Err(FileNameError::Version(_))));
+    }
+    
+    #[test]
+    fn test_file_name_parse() {
+        let file_name: FileName = "2024-07-30-00-56-25-031870928-0600_1-2-3".parse().unwrap();
+        assert_eq!(file_name.get_version(), &Version::new(1, 2, 3));
+        
+        assert!("not-a-file-name".parse::<FileName>().is_err());
+        assert!("2024-07-30_1-2-3".parse::<FileName>().is_err());
+    }
+    
+    #[test]
+    fn test_file_name_round_trip_preserves_offset() {
+        let timestamp = jiff::Timestamp::new(1_722_300_985, 31_870_928).unwrap();
+        
+        for offset_seconds in [-6 * 3600, 0, 5 * 3600 + 1800] {
+            let offset = jiff::tz::Offset::from_seconds(offset_seconds).unwrap();
+            let file_name = FileName {
+                datetime: timestamp.to_zoned(jiff::tz::TimeZone::fixed(offset)),
+                version: Version::new(1, 2, 3),
+            };
+            
+            let parsed = FileName::from_string(&file_name.try_to_string().unwrap()).unwrap();
+            assert_eq!(parsed.get_datetime().timestamp(), timestamp);
+            assert_eq!(parsed.get_datetime().offset(), offset);
+        }
+    }
+    
+    #[test]
+    fn test_file_name_round_trip_from_named_zone() {
+        let file_name = FileName::new(Version::new(1, 2, 3));
+        let parsed = FileName::from_string(&file_name.try_to_string().unwrap()).unwrap();
+        
+        assert_eq!(parsed.get_datetime().timestamp(), file_name.get_datetime().timestamp());
+        assert_eq!(parsed.get_datetime().offset(), file_name.get_datetime().offset());
+    }
+    
+    #[test]
+    fn test_file_name_new_utc() {
+        let file_name = FileName::new_utc(Version::new(1, 2, 3));
+        assert_eq!(file_name.get_datetime().offset(), jiff::tz::Offset::UTC);
+        
+        let serialized = file_name.try_to_string().unwrap();
+        assert!(serialized.ends_with("-PLUS-0000_1-2-3"), "{}", serialized);
+        
+        let parsed = FileName::from_string(&serialized).unwrap();
+        assert_eq!(parsed.get_datetime().timestamp(), file_name.get_datetime().timestamp());
+        assert_eq!(parsed.get_datetime().offset(), jiff::tz::Offset::UTC);
+    }
+    
+    #[test]
+    fn test_file_name_custom_format_round_trip() {
+        let datetime = jiff::Timestamp::from_second(1_722_300_985).unwrap().to_zoned(jiff::tz::TimeZone::fixed(jiff::tz::offset(5)));
+        let file_name = FileName {
+            datetime,
+            version: Version::new(1, 2, 3),
+        };
+        
+        let formatted = file_name.to_string_with_format("%Y%m%dT%H%M%S%z").unwrap();
+        assert_eq!(formatted, "20240730T055625-PLUS-0500_1-2-3");
+        
+        let parsed = FileName::from_string_with_format(&formatted, "%Y%m%dT%H%M%S%z").unwrap();
+        assert_eq!(parsed.get_datetime().timestamp(), file_name.get_datetime().timestamp());
+        assert_eq!(parsed.get_version(), &Version::new(1, 2, 3));
+    }
+    
+    #[test]
+    fn test_file_name_underscore_in_datetime_format() {
+        let datetime = jiff::Timestamp::from_second(1_722_300_985).unwrap().to_zoned(jiff::tz::TimeZone::fixed(jiff::tz::offset(-6)));
+        let file_name = FileName {
+            datetime,
+            version: Version::new(1, 2, 3),
+        };
+        
+        let formatted = file_name.to_string_with_format("%Y_%m_%d_%H%M%S%z").unwrap();
+        assert_eq!(formatted, "2024_07_29_185625-0600_1-2-3");
+        
+        let parsed = FileName::from_string_with_format(&formatted, "%Y_%m_%d_%H%M%S%z").unwrap();
+        assert_eq!(parsed.get_datetime().timestamp(), file_name.get_datetime().timestamp());
+        assert_eq!(parsed.get_version(), &Version::new(1, 2, 3));
+    }
+    
+    /// A small xorshift generator, so the random inputs are the same on every run.
+    fn next_random(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+    
+    /// Parses untrusted input, checking that anything accepted survives a round trip.
+    fn assert_parses_consistently(input: &str) {
+        if let Ok(file_name) = FileName::from_string(input) {
+            let reparsed = FileName::from_string(&file_name.try_to_string().unwrap()).unwrap();
+            assert_eq!(reparsed.get_datetime().timestamp(), file_name.get_datetime().timestamp());
+            assert_eq!(reparsed.get_version(), file_name.get_version());
+        }
+    }
+    
+    #[test]
+    fn test_file_name_random_input_never_panics() {
+        const ALPHABET: &[char] = &['0', '1', '2', '9', '-', '_', '+', '.', 'P', 'L', 'U', 'S', 'v', 'a', ' ', '/', '\\', 'é', '\u{0}'];
+        let valid = "2024-07-30-00-56-25-031870928-0600_1-2-3";
+        let mut state = 0x2545_f491_4f6c_dd1d;
+        
+        for _ in 0..2000 {
+            let length = (next_random(&mut state) % 48) as usize;
+            let random: String = (0..length)
+                .map(|_| ALPHABET[(next_random(&mut state) % ALPHABET.len() as u64) as usize])
+                .collect();
+            assert_parses_consistently(&random);
+            
+            let position = (next_random(&mut state) % valid.len() as u64) as usize;
+            assert_parses_consistently(&format!("{}{}", &valid[..position], random));
+        }
+        
+        for end in 0..valid.len() {
+            assert!(FileName::from_string(&valid[..end]).is_err(), "{:?} should be rejected", &valid[..end]);
+        }
+    }
+    
+    #[test]
+    fn test_file_name_eq_ignoring_subsecond() {
+        let name = |nanosecond| FileName {
+            datetime: jiff::Timestamp::new(1_722_300_985, nanosecond).unwrap().to_zoned(jiff::tz::TimeZone::UTC),
+            version: Version::new(1, 2, 3),
+        };
+        
+        assert!(name(1).eq_ignoring_subsecond(&name(999_999_999)));
+        assert_ne!(name(1), name(999_999_999));
+        
+        let later = FileName {
+            datetime: jiff::Timestamp::new(1_722_300_986, 1).unwrap().to_zoned(jiff::tz::TimeZone::UTC),
+            version: Version::new(1, 2, 3),
+        };
+        assert!(!name(1).eq_ignoring_subsecond(&later));
+        
+        let other_version = FileName {
+            version: Version::new(1, 2, 4),
+            ..name(1)
+        };
+        assert!(!name(1).eq_ignoring_subsecond(&other_version));
+    }
+    
+    #[test]
+    fn test_find_collisions() {
+        let datetime = jiff::Timestamp::new(1_722_300_985, 31_870_928).unwrap().to_zoned(jiff::tz::TimeZone::UTC);
+        let names = vec![
+            FileName { datetime: datetime.clone(), version: Version::new(1, 2, 3) },
+            FileName { datetime: datetime.clone(), version: Version::new(1, 2, 4) },
+            FileName { datetime: datetime.clone(), version: Version::new(1, 2, 3) },
+            FileName::new(Version::new(1, 2, 3)),
+            FileName { datetime, version: Version::new(1, 2, 3) },
+        ];
+        
+        assert_eq!(find_collisions(&names), vec![(0, 2), (0, 4), (2, 4)]);
+        assert!(find_collisions(&names[..2]).is_empty());
     }
 }
\ No newline at end of file
@@ -0,0 +1,60 @@
+use std::cell::Cell;
+use uuid::Uuid;
+
+/// A source of ids for items, tags and their instances.
+pub trait IdGenerator {
+    fn generate(&self) -> String;
+}
+
+/// Generates random v4 UUIDs. This is the generator used by the plain constructors.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UuidGenerator;
+
+impl IdGenerator for UuidGenerator {
+    fn generate(&self) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
+/// Generates the ids `1`, `2`, `3` and so on, for deterministic tests.
+#[derive(Debug, Default)]
+pub struct SequentialGenerator {
+    last: Cell<u64>,
+}
+
+impl SequentialGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl IdGenerator for SequentialGenerator {
+    fn generate(&self) -> String {
+        let next = self.last.get() + 1;
+        self.last.set(next);
+        next.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_sequential_generator() {
+        let generator = SequentialGenerator::new();
+        assert_eq!(generator.generate(), "1");
+        assert_eq!(generator.generate(), "2");
+        assert_eq!(generator.generate(), "3");
+    }
+    
+    #[test]
+    fn test_uuid_generator() {
+        let generator = UuidGenerator;
+        let id = generator.generate();
+        
+        assert!(Uuid::parse_str(&id).is_ok());
+        assert_ne!(id, generator.generate());
+    }
+
+}
\ No newline at end of file
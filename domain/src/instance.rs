@@ -1,59 +1,163 @@
 use std::cmp::PartialEq;
+use std::collections::BTreeMap;
 use std::fmt::Display;
-use jiff::Zoned;
+use std::str::FromStr;
+use jiff::{SignedDuration, Span, Zoned};
 use crate::version::{Version, VersionLevel};
 
+/// The default maximum length of a change note, in characters.
+pub const MAX_NOTE_LENGTH: usize = 1000;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Instance {
     datetime: Zoned,
     change_note: String,
     instance_type: InstanceType,
     version: Version,
+    author: Option<String>,
+    metadata: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InstanceType {
     Creation,
     Update,
     Deletion,
     Restoration,
+    Rename,
+    Touch,
 }
 
 impl Instance {
     pub fn create_initial_instance(version_level: VersionLevel) -> Self {
+        Self::create_initial_instance_at(version_level, Zoned::now())
+    }
+    
+    /// Like `create_initial_instance`, but stamped with `datetime` instead of the current time,
+    /// e.g. when importing historical data.
+    pub fn create_initial_instance_at(version_level: VersionLevel, datetime: Zoned) -> Self {
         Self {
-            datetime: Zoned::now(),
+            datetime,
             change_note: String::from("Instance Created"),
             instance_type: InstanceType::Creation,
-            version: Version::new(0, 0, 0).create_child_version(version_level),
+            version: Version::ZERO.create_child_version(version_level),
+            author: None,
+            metadata: BTreeMap::new(),
         }
     }
     
-    pub fn create_child_instance(&self, change_note: String, change_type: VersionLevel) -> Self {
-        Self {
-            datetime: Zoned::now(),
-            change_note,
+    pub fn create_child_instance(&self, change_note: String, change_type: VersionLevel) -> Result<Self, InstanceError> {
+        self.create_child_instance_at(change_note, change_type, Zoned::now())
+    }
+    
+    pub fn create_child_instance_at(&self, change_note: String, change_type: VersionLevel, datetime: Zoned) -> Result<Self, InstanceError> {
+        Ok(Self {
+            datetime,
+            change_note: validate_note(&change_note, "Instance Updated", MAX_NOTE_LENGTH)?,
             instance_type: InstanceType::Update,
             version: self.version.create_child_version(change_type),
-        }
+            author: None,
+            metadata: BTreeMap::new(),
+        })
     }
     
-    pub fn create_deletion_instance(&self, note: Option<String>) -> Self {
-        Self {
+    /// Like `create_child_instance`, but with an explicit version rather than an increment.
+    /// The version must be newer than this instance's version.
+    pub fn create_child_instance_with_version(&self, change_note: String, version: Version) -> Result<Self, InstanceError> {
+        if !version.is_newer_than(&self.version) {
+            return Err(InstanceError::VersionRegression(version.to_string(), self.version.to_string()));
+        }
+        
+        Ok(Self {
             datetime: Zoned::now(),
-            change_note: note.unwrap_or(String::from("Instance Deleted")),
+            change_note: validate_note(&change_note, "Instance Updated", MAX_NOTE_LENGTH)?,
+            instance_type: InstanceType::Update,
+            version,
+            author: None,
+            metadata: BTreeMap::new(),
+        })
+    }
+    
+    /// Like `create_child_instance`, but attributed to `author`.
+    pub fn create_child_instance_by(&self, change_note: String, change_type: VersionLevel, author: String) -> Result<Self, InstanceError> {
+        Ok(self.create_child_instance(change_note, change_type)?.with_author(author))
+    }
+    
+    /// Creates a deletion instance attributed to the same author as this instance; use
+    /// `with_author` to override it.
+    pub fn create_deletion_instance(&self, note: Option<String>) -> Result<Self, InstanceError> {
+        self.create_deletion_instance_at(note, Zoned::now())
+    }
+    
+    pub fn create_deletion_instance_at(&self, note: Option<String>, datetime: Zoned) -> Result<Self, InstanceError> {
+        Ok(Self {
+            datetime,
+            change_note: validate_note(note.as_deref().unwrap_or_default(), "Instance Deleted", MAX_NOTE_LENGTH)?,
             instance_type: InstanceType::Deletion,
             version: self.version.create_child_version(VersionLevel::Major),
-        }
+            author: self.author.clone(),
+            metadata: BTreeMap::new(),
+        })
     }
     
-    pub fn create_restoration_instance(&self, note: Option<String>) -> Self {
-        Self {
-            datetime: Zoned::now(),
-            change_note: note.unwrap_or(String::from("Instance restored")),
+    /// Creates a restoration instance attributed to the same author as this instance; use
+    /// `with_author` to override it.
+    pub fn create_restoration_instance(&self, note: Option<String>) -> Result<Self, InstanceError> {
+        self.create_restoration_instance_at(note, Zoned::now())
+    }
+    
+    pub fn create_restoration_instance_at(&self, note: Option<String>, datetime: Zoned) -> Result<Self, InstanceError> {
+        Ok(Self {
+            datetime,
+            change_note: validate_note(note.as_deref().unwrap_or_default(), "Instance restored", MAX_NOTE_LENGTH)?,
             instance_type: InstanceType::Restoration,
             version: self.version.create_child_version(VersionLevel::Major),
-        }
+            author: self.author.clone(),
+            metadata: BTreeMap::new(),
+        })
+    }
+    
+    /// Records a change to the name of the instanced object, e.g. its title or extension.
+    /// Renames don't change the content, so they always bump the patch version. The rename
+    /// is attributed to the same author as this instance.
+    pub fn create_rename_instance(&self, change_note: String) -> Result<Self, InstanceError> {
+        self.create_rename_instance_at(change_note, Zoned::now())
+    }
+    
+    pub fn create_rename_instance_at(&self, change_note: String, datetime: Zoned) -> Result<Self, InstanceError> {
+        Ok(Self {
+            datetime,
+            change_note: validate_note(&change_note, "Instance Renamed", MAX_NOTE_LENGTH)?,
+            instance_type: InstanceType::Rename,
+            version: self.version.create_child_version(VersionLevel::Patch),
+            author: self.author.clone(),
+            metadata: BTreeMap::new(),
+        })
+    }
+    
+    /// Records a metadata-only touch that keeps this instance's version, e.g. after adding a
+    /// tag. The touch is attributed to the same author as this instance.
+    pub fn create_touch_instance(&self, change_note: String) -> Result<Self, InstanceError> {
+        self.create_touch_instance_at(change_note, Zoned::now())
+    }
+    
+    pub fn create_touch_instance_at(&self, change_note: String, datetime: Zoned) -> Result<Self, InstanceError> {
+        Ok(Self {
+            datetime,
+            change_note: validate_note(&change_note, "Instance Touched", MAX_NOTE_LENGTH)?,
+            instance_type: InstanceType::Touch,
+            version: self.version,
+            author: self.author.clone(),
+            metadata: BTreeMap::new(),
+        })
+    }
+    
+    /// Attributes the instance to the given author.
+    pub fn with_author(mut self, author: String) -> Self {
+        self.author = Some(author);
+        self
     }
     
     pub fn get_version(&self) -> &Version {
@@ -64,19 +168,102 @@ impl Instance {
         &self.datetime
     }
     
+    /// How long ago the instance was created, relative to `now`. Negative if the instance
+    /// is later than `now`.
+    pub fn age(&self, now: &Zoned) -> SignedDuration {
+        now.duration_since(&self.datetime)
+    }
+    
     pub fn get_change_note(&self) -> &str {
         &self.change_note
     }
     
+    /// Replaces the change note, e.g. to fix a typo, without recording a new version. This
+    /// rewrites history, so it should only be used for explicit amendments. The note is
+    /// validated like a new one, and an empty note leaves the current note in place.
+    pub fn amend_note(&mut self, new_note: String) -> Result<(), InstanceError> {
+        self.change_note = validate_note(&new_note, &self.change_note, MAX_NOTE_LENGTH)?;
+        Ok(())
+    }
+    
+    pub fn get_author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+    
+    /// Attaches a piece of metadata to the instance, replacing any previous value for the key.
+    pub fn set_metadata(&mut self, key: String, value: String) {
+        self.metadata.insert(key, value);
+    }
+    
+    pub fn get_metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+    
+    /// Compares the meaningful content of two instances. `Instance` holds no generated id, so
+    /// this is the same as `==`; it exists to match `content_eq` on the instance wrappers.
+    pub fn content_eq(&self, other: &Instance) -> bool {
+        self == other
+    }
+    
     pub fn is_type_of(&self, instance_type: InstanceType) -> bool {
         self.instance_type == instance_type
     }
 }
 
+/// Trims a change note, substituting `default` for an empty note, and rejects notes longer
+/// than `max_length` characters. Instance constructors use `MAX_NOTE_LENGTH`.
+pub fn validate_note(note: &str, default: &str, max_length: usize) -> Result<String, InstanceError> {
+    let note = note.trim();
+    if note.is_empty() {
+        return Ok(String::from(default));
+    }
+    
+    if note.chars().count() > max_length {
+        return Err(InstanceError::NoteTooLong(max_length));
+    }
+    
+    Ok(String::from(note))
+}
+
+impl Display for InstanceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            InstanceType::Creation => write!(f, "Created"),
+            InstanceType::Update => write!(f, "Updated"),
+            InstanceType::Deletion => write!(f, "Deleted"),
+            InstanceType::Restoration => write!(f, "Restored"),
+            InstanceType::Rename => write!(f, "Renamed"),
+            InstanceType::Touch => write!(f, "Touched"),
+        }
+    }
+}
+
+impl FromStr for InstanceType {
+    type Err = InstanceError;
+
+    /// Parses the `Display` form of an instance type, ignoring case.
+    fn from_str(instance_type: &str) -> Result<Self, Self::Err> {
+        match instance_type.to_lowercase().as_str() {
+            "created" => Ok(InstanceType::Creation),
+            "updated" => Ok(InstanceType::Update),
+            "deleted" => Ok(InstanceType::Deletion),
+            "restored" => Ok(InstanceType::Restoration),
+            "renamed" => Ok(InstanceType::Rename),
+            "touched" => Ok(InstanceType::Touch),
+            _ => Err(InstanceError::InvalidInstanceType(instance_type.to_string())),
+        }
+    }
+}
+
 pub trait Instanced {
     fn get_instance(&self) -> &Instance;
 }
 
+pub trait InstancedMut: Instanced {
+    fn get_instance_mut(&mut self) -> &mut Instance;
+}
+
+#[derive(Clone)]
 pub struct InstanceList<T: Instanced> {
     instances: Vec<T>,
 }
@@ -90,25 +277,121 @@ impl<T: Instanced> InstanceList<T> {
         }
     }
 
+    /// An empty list with room for `capacity` instances, e.g. before a bulk import.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            instances: Vec::with_capacity(capacity),
+        }
+    }
+    
+    /// Like `new`, but skips the sort. The caller must guarantee the values are already in
+    /// chronological order; otherwise `latest`, `instances_between` and the other queries
+    /// return wrong results. The order is only checked in debug builds, and no other rule
+    /// of `add` is checked at all, so use `try_new` for untrusted data.
+    pub fn new_unchecked(values: Vec<T>) -> Self {
+        debug_assert!(
+            values.windows(2).all(|pair| pair[0].get_instance().datetime <= pair[1].get_instance().datetime),
+            "InstanceList::new_unchecked called with unordered instances",
+        );
+        
+        Self {
+            instances: values,
+        }
+    }
+    
+    /// Reserves room for at least `additional` more instances.
+    pub fn reserve(&mut self, additional: usize) {
+        self.instances.reserve(additional);
+    }
+
+    /// Like `new`, but checks the values are already a valid history instead of sorting
+    /// them: datetimes must be in order and a deletion may only be followed by a restoration.
+    pub fn try_new(values: Vec<T>) -> Result<Self, InstanceError> {
+        let mut instance_list = Self {
+            instances: Vec::with_capacity(values.len()),
+        };
+        
+        for value in values {
+            instance_list.add(value)?;
+        }
+        
+        Ok(instance_list)
+    }
+    
+    /// Appends a new instance. The new instance must not be older than the latest one; an
+    /// equal datetime is accepted (the comparison is `<`, not `<=`), since instances created
+    /// in quick succession or restored from persisted data can share a timestamp.
     pub fn add(&mut self, new_instance: T) -> Result<(), InstanceError> {
-        match self.latest() {
-            Some(last_instance) => {
-                if new_instance.get_instance().datetime < last_instance.get_instance().datetime {
-                    return Err(InstanceError::DatetimeIncorrectlyOrdered);
-                }
+        if let Some(last_instance) = self.latest() {
+            if new_instance.get_instance().datetime < last_instance.get_instance().datetime {
+                return Err(InstanceError::DatetimeIncorrectlyOrdered);
             }
-            _ => (),
         }
         
         if self.is_deleted() && !new_instance.get_instance().is_type_of(InstanceType::Restoration) {
             return Err(InstanceError::CannotAddToDeletedInstanceList);
         }
+        
+        if !self.is_deleted() && new_instance.get_instance().is_type_of(InstanceType::Restoration) {
+            return Err(InstanceError::CannotRestoreNonDeleted);
+        }
 
         self.instances.push(new_instance);
         
         Ok(())
     }
 
+    /// Removes and returns the latest instance. This destructively rewrites history, unlike
+    /// recording a restoration instance. The initial instance is never removed, so `None`
+    /// is returned once only one instance remains.
+    pub fn undo_last(&mut self) -> Option<T> {
+        if self.instances.len() <= 1 {
+            return None;
+        }
+        
+        self.instances.pop()
+    }
+
+    /// Removes the instances from before `cutoff`, returning how many were removed. The
+    /// latest instance is always kept, and a kept restoration keeps its deletion, so the
+    /// remaining history is still valid.
+    pub fn truncate_before(&mut self, cutoff: &Zoned) -> usize {
+        let before_cutoff = self.instances
+            .iter()
+            .take_while(|instance| instance.get_instance().datetime < *cutoff)
+            .count();
+        
+        self.remove_earliest(before_cutoff)
+    }
+    
+    /// Removes all but the `n` most recent instances, returning how many were removed. At
+    /// least one instance is always kept, and, as with `truncate_before`, a kept restoration
+    /// keeps its deletion, so one more than `n` may remain.
+    pub fn keep_latest(&mut self, n: usize) -> usize {
+        self.remove_earliest(self.instances.len().saturating_sub(n))
+    }
+    
+    /// Removes up to `count` of the earliest instances, keeping the latest instance and the
+    /// deletion before a leading restoration.
+    fn remove_earliest(&mut self, count: usize) -> usize {
+        if self.instances.is_empty() {
+            return 0;
+        }
+        
+        let mut removed = count.min(self.instances.len() - 1);
+        while removed > 0 && self.instances[removed].get_instance().is_type_of(InstanceType::Restoration) {
+            removed -= 1;
+        }
+        
+        self.instances.drain(..removed);
+        removed
+    }
+
+    /// The instance at `index`, where index 0 is the earliest.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.instances.get(index)
+    }
+    
     pub fn latest(&self) -> Option<&T> {
         self.instances.last()
     }
@@ -121,31 +404,248 @@ impl<T: Instanced> InstanceList<T> {
         self.instances.len()
     }
     
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+    
+    pub fn count_by_type(&self, instance_type: InstanceType) -> usize {
+        self.filter_by_type(instance_type).count()
+    }
+    
+    /// Iterates over the instances of the given type in chronological order.
+    pub fn filter_by_type(&self, instance_type: InstanceType) -> impl Iterator<Item = &T> {
+        self.instances.iter().filter(move |instance| instance.get_instance().is_type_of(instance_type))
+    }
+    
+    /// The most recent instance of the given type, scanning backward from the latest.
+    pub fn latest_of_type(&self, instance_type: InstanceType) -> Option<&T> {
+        self.instances.iter().rev().find(|instance| instance.get_instance().is_type_of(instance_type))
+    }
+    
+    /// The most recent instance whose version has no pre-release identifier, skipping any
+    /// trailing pre-release builds.
+    pub fn latest_stable(&self) -> Option<&T> {
+        self.instances.iter().rev().find(|instance| instance.get_instance().get_version().get_pre_release().is_none())
+    }
+    
+    /// The version of each instance, in order.
+    pub fn versions(&self) -> Vec<Version> {
+        self.instances.iter().map(|instance| *instance.get_instance().get_version()).collect()
+    }
+    
+    /// Finds the instance carrying the given version.
+    pub fn get_by_version(&self, version: &Version) -> Option<&T> {
+        self.instances.iter().find(|instance| instance.get_instance().get_version() == version)
+    }
+    
+    /// Returns, in chronological order, every instance whose datetime falls within the
+    /// inclusive range. An empty vec is returned when `start` is after `end`.
+    pub fn instances_between(&self, start: &Zoned, end: &Zoned) -> Vec<&T> {
+        if start > end {
+            return Vec::new();
+        }
+        
+        self.instances
+            .iter()
+            .skip_while(|instance| instance.get_instance().datetime < *start)
+            .take_while(|instance| instance.get_instance().datetime <= *end)
+            .collect()
+    }
+    
+    /// The span between each pair of adjacent instances, in order, so there is one fewer
+    /// span than there are instances. Spans are measured in units up to hours.
+    pub fn durations(&self) -> Vec<Span> {
+        self.instances
+            .windows(2)
+            .map(|pair| pair[1].get_instance().get_datetime() - pair[0].get_instance().get_datetime())
+            .collect()
+    }
+    
+    /// One line per instance, newest first, e.g. `1.2.0 (2024-07-30) [Updated] Test Change`.
+    /// The author is shown before the note when present: `[Updated] alice: Test Change`.
+    pub fn format_changelog(&self) -> String {
+        self.instances
+            .iter()
+            .rev()
+            .map(|instance| {
+                let instance = instance.get_instance();
+                let date = instance.datetime.strftime("%Y-%m-%d");
+                match instance.get_author() {
+                    Some(author) => format!("{} ({}) [{}] {}: {}", instance.version, date, instance.instance_type, author, instance.change_note),
+                    None => format!("{} ({}) [{}] {}", instance.version, date, instance.instance_type, instance.change_note),
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+    
+    /// Iterates over the instances in chronological order, earliest first. The iterator is
+    /// double-ended, so `iter().rev()` also works.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.instances.iter()
+    }
+    
+    /// Iterates over the instances newest first.
+    pub fn iter_rev(&self) -> impl Iterator<Item = &T> {
+        self.instances.iter().rev()
+    }
+    
     pub fn is_deleted(&self) -> bool {
         match self.latest() {
             Some(instance) => instance.get_instance().is_type_of(InstanceType::Deletion),
             None => false,
         }
     }
+    
+    /// When the list was deleted, or `None` if it isn't currently deleted (including when
+    /// it was deleted and later restored).
+    pub fn deleted_at(&self) -> Option<&Zoned> {
+        if !self.is_deleted() {
+            return None;
+        }
+        
+        self.latest_of_type(InstanceType::Deletion).map(|instance| instance.get_instance().get_datetime())
+    }
 }
 
-#[derive(Debug)]
-pub enum InstanceError {
-    CannotAddToDeletedInstanceList,
-    DatetimeIncorrectlyOrdered,
+impl<T: InstancedMut> InstanceList<T> {
+    /// Collapses the run of trailing `Update` instances into a single update. The run stops
+    /// at any other instance type, so it never crosses a deletion or restoration. The
+    /// remaining instance keeps the state of the latest update, the highest version of the
+    /// run and the datetime of its earliest instance. Runs of fewer than two updates are
+    /// left untouched. The combined note is validated like any other change note.
+    pub fn squash_updates(&mut self, combined_note: String) -> Result<(), InstanceError> {
+        let combined_note = validate_note(&combined_note, "Instance Updated", MAX_NOTE_LENGTH)?;
+        
+        let run_length = self.instances
+            .iter()
+            .rev()
+            .take_while(|instance| instance.get_instance().is_type_of(InstanceType::Update))
+            .count();
+        
+        if run_length < 2 {
+            return Ok(());
+        }
+        
+        let run_start = self.instances.len() - run_length;
+        let run = &self.instances[run_start..];
+        let datetime = run[0].get_instance().datetime.clone();
+        let version = run.iter().map(|instance| instance.get_instance().version).max();
+        
+        let mut squashed = self.instances.pop().unwrap();
+        self.instances.truncate(run_start);
+        
+        let instance = squashed.get_instance_mut();
+        instance.change_note = combined_note;
+        instance.datetime = datetime;
+        if let Some(version) = version {
+            instance.version = version;
+        }
+        
+        self.instances.push(squashed);
+        
+        Ok(())
+    }
+    
+    /// Merges another history into this one in datetime order, keeping this list's
+    /// instances first when datetimes are equal. Only the earliest instance stays a
+    /// creation; later creations become updates. An instance whose version isn't newer than
+    /// the one before it is re-versioned by the level it changed by in its own history
+    /// (major for a former creation), so versions keep increasing; touches take the version
+    /// before them. Fails, leaving the list
+    /// unchanged, if the merged deletions and restorations don't form a valid history.
+    pub fn merge(&mut self, other: &InstanceList<T>) -> Result<(), InstanceError>
+    where
+        T: Clone,
+    {
+        let mut combined: Vec<(T, Option<VersionLevel>)> = Vec::with_capacity(self.len() + other.len());
+        for list in [&*self, other] {
+            let mut previous_version: Option<Version> = None;
+            for value in list.iter() {
+                let version = value.get_instance().version;
+                combined.push((value.clone(), previous_version.and_then(|previous| previous.diff(&version))));
+                previous_version = Some(version);
+            }
+        }
+        
+        combined.sort_by(|(a, _), (b, _)| a.get_instance().datetime.cmp(&b.get_instance().datetime));
+        
+        let mut merged = Vec::with_capacity(combined.len());
+        let mut previous_version: Option<Version> = None;
+        for (mut value, level) in combined {
+            let instance = value.get_instance_mut();
+            if let Some(previous) = previous_version {
+                if instance.is_type_of(InstanceType::Creation) {
+                    instance.instance_type = InstanceType::Update;
+                }
+                
+                if instance.is_type_of(InstanceType::Touch) {
+                    instance.version = previous;
+                } else if !instance.version.is_newer_than(&previous) {
+                    instance.version = previous.create_child_version(level.unwrap_or(VersionLevel::Major));
+                }
+            }
+            
+            previous_version = Some(instance.version);
+            merged.push(value);
+        }
+        
+        *self = InstanceList::try_new(merged)?;
+        
+        Ok(())
+    }
 }
 
-impl std::error::Error for InstanceError {}
+/// Serialized as the plain sequence of instances.
+#[cfg(feature = "serde")]
+impl<T: Instanced + serde::Serialize> serde::Serialize for InstanceList<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(&self.instances)
+    }
+}
 
-impl Display for InstanceError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            InstanceError::CannotAddToDeletedInstanceList => write!(f, "Cannot add to a deleted instance list"),
-            InstanceError::DatetimeIncorrectlyOrdered => write!(f, "New instance datetime is before the latest instance datetime"),
-        }
+/// Deserialized through `InstanceList::new`, so the chronological ordering holds.
+#[cfg(feature = "serde")]
+impl<'de, T: Instanced + serde::Deserialize<'de>> serde::Deserialize<'de> for InstanceList<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(InstanceList::new)
+    }
+}
+
+/// Indexes chronologically from the earliest instance, panicking when out of range like `Vec`.
+impl<T: Instanced> std::ops::Index<usize> for InstanceList<T> {
+    type Output = T;
+    
+    fn index(&self, index: usize) -> &T {
+        &self.instances[index]
+    }
+}
+
+impl<'a, T: Instanced> IntoIterator for &'a InstanceList<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum InstanceError {
+    #[error("Cannot add to a deleted instance list")]
+    CannotAddToDeletedInstanceList,
+    #[error("Cannot restore an instance list that isn't deleted")]
+    CannotRestoreNonDeleted,
+    #[error("New instance datetime is before the latest instance datetime")]
+    DatetimeIncorrectlyOrdered,
+    #[error("Invalid instance type: {0}")]
+    InvalidInstanceType(String),
+    #[error("Change note is longer than {0} characters")]
+    NoteTooLong(usize),
+    #[error("Version {0} is not newer than the current version {1}")]
+    VersionRegression(String, String),
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,7 +662,9 @@ mod tests {
                     datetime: self.instance.datetime.clone(),
                     change_note: self.instance.change_note.clone(),
                     instance_type: self.instance.instance_type.clone(),
-                    version: self.instance.version.clone(),
+                    version: self.instance.version,
+                    author: self.instance.author.clone(),
+                    metadata: self.instance.metadata.clone(),
                 }
             }
         }
@@ -174,6 +676,35 @@ mod tests {
         }
     }
     
+    impl InstancedMut for TestInstance {
+        fn get_instance_mut(&mut self) -> &mut Instance {
+            &mut self.instance
+        }
+    }
+    
+    fn create_history(notes: &[&str]) -> Vec<TestInstance> {
+        let mut instances = vec![TestInstance {
+            instance: Instance::create_initial_instance(VersionLevel::Minor),
+        }];
+        
+        for note in notes {
+            let instance = instances.last().unwrap().get_instance().create_child_instance(note.to_string(), VersionLevel::Patch).unwrap();
+            instances.push(TestInstance { instance });
+        }
+        
+        instances
+    }
+    
+    fn create_history_at(datetimes: &[&str]) -> Vec<TestInstance> {
+        let mut instances = create_history(&vec!["Test Change"; datetimes.len() - 1]);
+        
+        for (instance, datetime) in instances.iter_mut().zip(datetimes) {
+            instance.instance.datetime = datetime.parse().unwrap();
+        }
+        
+        instances
+    }
+    
     #[test]
     fn test_instance_list() {
         let instance1 = TestInstance {
@@ -181,11 +712,11 @@ mod tests {
         };
         
         let instance2 = TestInstance {
-            instance: instance1.get_instance().create_child_instance(String::from("Test Change"), VersionLevel::Patch),
+            instance: instance1.get_instance().create_child_instance(String::from("Test Change"), VersionLevel::Patch).unwrap(),
         };
         
         let instance3 = TestInstance {
-            instance: instance2.get_instance().create_child_instance(String::from("Test Change 2"), VersionLevel::Patch),
+            instance: instance2.get_instance().create_child_instance(String::from("Test Change 2"), VersionLevel::Patch).unwrap(),
         };
         
         let mut instance_list = InstanceList::new(vec![instance1, instance2]);
@@ -199,7 +730,7 @@ mod tests {
         assert_eq!(instance_list.latest().unwrap().get_instance().get_change_note(), "Test Change 2");
         
         let instance4 = TestInstance {
-            instance: instance3.get_instance().create_deletion_instance(None),
+            instance: instance3.get_instance().create_deletion_instance(None).unwrap(),
         };
         
         instance_list.add(instance4.clone()).unwrap();
@@ -208,7 +739,7 @@ mod tests {
         assert_eq!(instance_list.latest().unwrap().get_instance().is_type_of(InstanceType::Deletion), true);
         
         let instance5 = TestInstance {
-            instance: instance4.get_instance().create_restoration_instance(None),
+            instance: instance4.get_instance().create_restoration_instance(None).unwrap(),
         };
         
         instance_list.add(instance5).unwrap();
@@ -216,4 +747,746 @@ mod tests {
         assert_eq!(instance_list.len(), 5);
         assert_eq!(instance_list.latest().unwrap().get_instance().is_type_of(InstanceType::Deletion), false);
     }
+    
+    #[test]
+    fn test_instance_list_with_capacity_and_reserve() {
+        let mut instance_list: InstanceList<TestInstance> = InstanceList::with_capacity(16);
+        assert!(instance_list.is_empty());
+        assert!(instance_list.latest().is_none());
+        assert!(instance_list.instances.capacity() >= 16);
+        
+        for instance in create_history(&["Test Change"]) {
+            instance_list.add(instance).unwrap();
+        }
+        
+        instance_list.reserve(100);
+        assert_eq!(instance_list.len(), 2);
+        assert!(instance_list.instances.capacity() >= 102);
+    }
+    
+    #[test]
+    fn test_instance_list_new_unchecked() {
+        let instance_list = InstanceList::new_unchecked(create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T11:00:00+00:00[UTC]",
+            "2024-07-30T12:00:00+00:00[UTC]",
+        ]));
+        
+        assert_eq!(instance_list.len(), 3);
+        assert!(instance_list.earliest().unwrap().get_instance().is_type_of(InstanceType::Creation));
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_datetime(), &"2024-07-30T12:00:00+00:00[UTC]".parse::<Zoned>().unwrap());
+    }
+    
+    #[test]
+    fn test_instance_list_iter() {
+        let instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        
+        let notes: Vec<&str> = instance_list.iter().map(|instance| instance.get_instance().get_change_note()).collect();
+        assert_eq!(notes, vec!["Instance Created", "Test Change", "Test Change 2"]);
+        
+        let mut count = 0;
+        for instance in &instance_list {
+            assert_eq!(instance.get_instance().get_change_note(), notes[count]);
+            count += 1;
+        }
+        assert_eq!(count, 3);
+    }
+    
+    #[test]
+    fn test_instance_list_iter_rev() {
+        let instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        
+        let notes: Vec<&str> = instance_list.iter_rev().map(|instance| instance.get_instance().get_change_note()).collect();
+        assert_eq!(notes, vec!["Test Change 2", "Test Change", "Instance Created"]);
+        
+        let reversed: Vec<&str> = instance_list.iter().rev().map(|instance| instance.get_instance().get_change_note()).collect();
+        assert_eq!(reversed, notes);
+    }
+    
+    #[test]
+    fn test_instance_list_get_and_index() {
+        let instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        
+        assert!(instance_list.get(0).unwrap().get_instance().is_type_of(InstanceType::Creation));
+        assert_eq!(instance_list.get(1).unwrap().get_instance().get_change_note(), "Test Change");
+        assert!(instance_list.get(3).is_none());
+        
+        assert!(instance_list[0].get_instance().is_type_of(InstanceType::Creation));
+        assert_eq!(instance_list[1].get_instance().get_change_note(), "Test Change");
+        assert_eq!(instance_list[2].get_instance().get_change_note(), "Test Change 2");
+    }
+    
+    #[test]
+    #[should_panic]
+    fn test_instance_list_index_out_of_range() {
+        let instance_list = InstanceList::new(create_history(&["Test Change"]));
+        let _ = &instance_list[2];
+    }
+    
+    #[test]
+    fn test_instance_list_get_by_version() {
+        let instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        
+        let instance = instance_list.get_by_version(&Version::new(0, 1, 1)).unwrap();
+        assert_eq!(instance.get_instance().get_change_note(), "Test Change");
+        
+        let instance = instance_list.get_by_version(&Version::new(0, 1, 0)).unwrap();
+        assert_eq!(instance.get_instance().get_change_note(), "Instance Created");
+        
+        assert!(instance_list.get_by_version(&Version::new(1, 0, 0)).is_none());
+    }
+    
+    #[test]
+    fn test_instance_list_instances_between() {
+        let instance_list = InstanceList::new(create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T11:00:00+00:00[UTC]",
+            "2024-07-30T12:00:00+00:00[UTC]",
+            "2024-07-30T13:00:00+00:00[UTC]",
+        ]));
+        
+        let start: Zoned = "2024-07-30T11:00:00+00:00[UTC]".parse().unwrap();
+        let end: Zoned = "2024-07-30T12:00:00+00:00[UTC]".parse().unwrap();
+        let versions: Vec<String> = instance_list
+            .instances_between(&start, &end)
+            .iter()
+            .map(|instance| instance.get_instance().get_version().to_string())
+            .collect();
+        assert_eq!(versions, vec!["0.1.1", "0.1.2"]);
+        
+        let start: Zoned = "2024-07-30T10:30:00+00:00[UTC]".parse().unwrap();
+        let end: Zoned = "2024-07-30T08:00:00-06:00[America/Denver]".parse().unwrap();
+        assert_eq!(instance_list.instances_between(&start, &end).len(), 3);
+        
+        let start: Zoned = "2024-07-30T14:00:00+00:00[UTC]".parse().unwrap();
+        let end: Zoned = "2024-07-30T15:00:00+00:00[UTC]".parse().unwrap();
+        assert!(instance_list.instances_between(&start, &end).is_empty());
+    }
+    
+    #[test]
+    fn test_instance_list_instances_between_reversed_range() {
+        let instance_list = InstanceList::new(create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T11:00:00+00:00[UTC]",
+        ]));
+        
+        let start: Zoned = "2024-07-30T12:00:00+00:00[UTC]".parse().unwrap();
+        let end: Zoned = "2024-07-30T09:00:00+00:00[UTC]".parse().unwrap();
+        assert!(instance_list.instances_between(&start, &end).is_empty());
+    }
+    
+    #[test]
+    fn test_instance_list_durations() {
+        let instance_list = InstanceList::new(create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T11:30:00+00:00[UTC]",
+            "2024-08-02T11:30:00+00:00[UTC]",
+        ]));
+        
+        let durations = instance_list.durations();
+        assert_eq!(durations.len(), 2);
+        assert_eq!((durations[0].get_hours(), durations[0].get_minutes()), (1, 30));
+        assert_eq!((durations[1].get_hours(), durations[1].get_minutes()), (72, 0));
+        
+        let single = InstanceList::new(create_history(&[]));
+        assert!(single.durations().is_empty());
+    }
+    
+    #[test]
+    fn test_instance_list_format_changelog() {
+        let mut instances = create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-31T11:00:00+00:00[UTC]",
+        ]);
+        let update = instances[1].instance
+            .create_child_instance_at(String::from("Test Change 2"), VersionLevel::Minor, "2024-08-02T09:00:00+00:00[UTC]".parse().unwrap())
+            .unwrap()
+            .with_author(String::from("alice"));
+        instances.push(TestInstance { instance: update });
+        let instance_list = InstanceList::new(instances);
+        
+        assert_eq!(
+            instance_list.format_changelog(),
+            "0.2.0 (2024-08-02) [Updated] alice: Test Change 2\n\
+             0.1.1 (2024-07-31) [Updated] Test Change\n\
+             0.1.0 (2024-07-30) [Created] Instance Created",
+        );
+    }
+    
+    #[test]
+    fn test_instance_list_undo_last() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        
+        let undone = instance_list.undo_last().unwrap();
+        assert_eq!(undone.get_instance().get_change_note(), "Test Change 2");
+        assert_eq!(instance_list.len(), 2);
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_change_note(), "Test Change");
+        
+        instance_list.undo_last().unwrap();
+        assert_eq!(instance_list.len(), 1);
+        
+        assert!(instance_list.undo_last().is_none());
+        assert_eq!(instance_list.len(), 1);
+        assert!(instance_list.latest().unwrap().get_instance().is_type_of(InstanceType::Creation));
+    }
+    
+    #[test]
+    fn test_instance_list_count_by_type() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        assert!(!instance_list.is_empty());
+        
+        let deletion = instance_list.latest().unwrap().get_instance().create_deletion_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: deletion }).unwrap();
+        
+        let restoration = instance_list.latest().unwrap().get_instance().create_restoration_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: restoration }).unwrap();
+        
+        let update = instance_list.latest().unwrap().get_instance().create_child_instance(String::from("Test Change 3"), VersionLevel::Patch).unwrap();
+        instance_list.add(TestInstance { instance: update }).unwrap();
+        
+        assert_eq!(instance_list.count_by_type(InstanceType::Creation), 1);
+        assert_eq!(instance_list.count_by_type(InstanceType::Update), 3);
+        assert_eq!(instance_list.count_by_type(InstanceType::Deletion), 1);
+        assert_eq!(instance_list.count_by_type(InstanceType::Restoration), 1);
+    }
+    
+    #[test]
+    fn test_instance_list_is_empty() {
+        let instance_list: InstanceList<TestInstance> = InstanceList::new(Vec::new());
+        assert!(instance_list.is_empty());
+        assert_eq!(instance_list.count_by_type(InstanceType::Creation), 0);
+    }
+    
+    #[test]
+    fn test_instance_author() {
+        let initial = Instance::create_initial_instance(VersionLevel::Minor);
+        assert_eq!(initial.get_author(), None);
+        
+        let edit = initial.create_child_instance(String::from("Test Change"), VersionLevel::Patch).unwrap().with_author(String::from("alice"));
+        assert_eq!(edit.get_author(), Some("alice"));
+        assert_eq!(edit.get_change_note(), "Test Change");
+        
+        let deletion = edit.create_deletion_instance(None).unwrap().with_author(String::from("bob"));
+        assert_eq!(deletion.get_author(), Some("bob"));
+        assert!(deletion.is_type_of(InstanceType::Deletion));
+        
+        let restoration = deletion.create_restoration_instance(None).unwrap();
+        assert_eq!(restoration.get_author(), Some("bob"));
+        assert_eq!(restoration.get_version(), &Version::new(2, 0, 0));
+    }
+    
+    #[test]
+    fn test_instance_author_inheritance() {
+        let initial = Instance::create_initial_instance(VersionLevel::Minor);
+        
+        let edit = initial.create_child_instance_by(String::from("Test Change"), VersionLevel::Patch, String::from("alice")).unwrap();
+        assert_eq!(edit.get_author(), Some("alice"));
+        assert_eq!(edit.get_version(), &Version::new(0, 1, 1));
+        
+        let deletion = edit.create_deletion_instance(None).unwrap();
+        assert_eq!(deletion.get_author(), Some("alice"));
+        
+        let restoration = deletion.create_restoration_instance(None).unwrap();
+        assert_eq!(restoration.get_author(), Some("alice"));
+        
+        let override_restoration = deletion.create_restoration_instance(None).unwrap().with_author(String::from("carol"));
+        assert_eq!(override_restoration.get_author(), Some("carol"));
+        
+        let unattributed = initial.create_deletion_instance(None).unwrap();
+        assert_eq!(unattributed.get_author(), None);
+    }
+    
+    #[test]
+    fn test_rename_and_touch_author_inheritance() {
+        let initial = Instance::create_initial_instance(VersionLevel::Minor);
+        let edit = initial.create_child_instance_by(String::from("Test Change"), VersionLevel::Patch, String::from("alice")).unwrap();
+        
+        let rename = edit.create_rename_instance(String::from("Renamed")).unwrap();
+        assert_eq!(rename.get_author(), Some("alice"));
+        
+        let touch = rename.create_touch_instance(String::from("Touched")).unwrap();
+        assert_eq!(touch.get_author(), Some("alice"));
+        assert_eq!(touch.get_version(), rename.get_version());
+        assert!(touch.is_type_of(InstanceType::Touch));
+        
+        assert_eq!(initial.create_rename_instance(String::from("Renamed")).unwrap().get_author(), None);
+        assert_eq!(initial.create_touch_instance(String::from("Touched")).unwrap().get_author(), None);
+    }
+    
+    #[test]
+    fn test_rename_and_touch_instance_at() {
+        let datetime = "2024-03-01T12:00:00+00:00[UTC]".parse::<Zoned>().unwrap();
+        let initial = Instance::create_initial_instance(VersionLevel::Minor);
+        
+        let rename = initial.create_rename_instance_at(String::from("Renamed"), datetime.clone()).unwrap();
+        assert_eq!(rename.get_datetime(), &datetime);
+        assert!(rename.is_type_of(InstanceType::Rename));
+        
+        let touch = rename.create_touch_instance_at(String::from("Touched"), datetime.clone()).unwrap();
+        assert_eq!(touch.get_datetime(), &datetime);
+        assert_eq!(touch.get_version(), rename.get_version());
+    }
+    
+    #[test]
+    fn test_rename_instance() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change"]));
+        
+        let rename = instance_list.latest().unwrap().get_instance().create_rename_instance(String::from("Renamed")).unwrap();
+        assert!(rename.is_type_of(InstanceType::Rename));
+        assert_eq!(rename.get_version(), &Version::new(0, 1, 2));
+        
+        instance_list.add(TestInstance { instance: rename }).unwrap();
+        assert!(!instance_list.is_deleted());
+        assert_eq!(instance_list.count_by_type(InstanceType::Rename), 1);
+        assert_eq!(instance_list.count_by_type(InstanceType::Update), 1);
+    }
+    
+    #[test]
+    fn test_touch_instance_is_not_an_update() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        
+        let touch = instance_list.latest().unwrap().get_instance().create_touch_instance(String::from("Tagged")).unwrap();
+        instance_list.add(TestInstance { instance: touch }).unwrap();
+        
+        assert_eq!(instance_list.count_by_type(InstanceType::Update), 2);
+        assert_eq!(instance_list.count_by_type(InstanceType::Touch), 1);
+        assert_eq!(instance_list.filter_by_type(InstanceType::Update).count(), 2);
+        
+        instance_list.squash_updates(String::from("Combined Change")).unwrap();
+        assert_eq!(instance_list.len(), 4);
+    }
+    
+    #[test]
+    fn test_instance_list_keep_latest() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2", "Test Change 3", "Test Change 4"]));
+        assert_eq!(instance_list.len(), 5);
+        
+        assert_eq!(instance_list.keep_latest(2), 3);
+        assert_eq!(instance_list.len(), 2);
+        assert_eq!(instance_list.earliest().unwrap().get_instance().get_change_note(), "Test Change 3");
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_change_note(), "Test Change 4");
+        
+        assert_eq!(instance_list.keep_latest(5), 0);
+        assert_eq!(instance_list.keep_latest(0), 1);
+        assert_eq!(instance_list.len(), 1);
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_change_note(), "Test Change 4");
+    }
+    
+    #[test]
+    fn test_instance_list_keep_latest_keeps_deletion_of_restoration() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change"]));
+        
+        let deletion = instance_list.latest().unwrap().get_instance().create_deletion_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: deletion }).unwrap();
+        let restoration = instance_list.latest().unwrap().get_instance().create_restoration_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: restoration }).unwrap();
+        
+        assert_eq!(instance_list.keep_latest(1), 2);
+        assert_eq!(instance_list.len(), 2);
+        assert!(instance_list.earliest().unwrap().get_instance().is_type_of(InstanceType::Deletion));
+        assert!(!instance_list.is_deleted());
+    }
+    
+    #[test]
+    fn test_instances_at_fixed_times() {
+        let created: Zoned = "2024-07-30T10:00:00+00:00[UTC]".parse().unwrap();
+        let updated: Zoned = "2024-07-30T11:00:00-06:00[America/Denver]".parse().unwrap();
+        let deleted: Zoned = "2024-07-31T09:30:00+00:00[UTC]".parse().unwrap();
+        let restored: Zoned = "2024-08-01T00:00:00+09:00[Asia/Tokyo]".parse().unwrap();
+        
+        let initial = Instance::create_initial_instance_at(VersionLevel::Minor, created.clone());
+        let update = initial.create_child_instance_at(String::from("Test Change"), VersionLevel::Patch, updated.clone()).unwrap();
+        let deletion = update.create_deletion_instance_at(None, deleted.clone()).unwrap();
+        let restoration = deletion.create_restoration_instance_at(None, restored.clone()).unwrap();
+        
+        assert_eq!(initial.get_datetime(), &created);
+        assert_eq!(update.get_datetime(), &updated);
+        assert_eq!(update.get_datetime().time_zone().iana_name(), Some("America/Denver"));
+        assert_eq!(deletion.get_datetime(), &deleted);
+        assert_eq!(restoration.get_datetime(), &restored);
+        assert!(restoration.is_type_of(InstanceType::Restoration));
+        
+        let instance_list = InstanceList::try_new(
+            [initial, update, deletion, restoration].into_iter().map(|instance| TestInstance { instance }).collect(),
+        ).unwrap();
+        assert_eq!(instance_list.earliest().unwrap().get_instance().get_datetime(), &created);
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_datetime(), &restored);
+    }
+    
+    #[test]
+    fn test_instance_age() {
+        let now: Zoned = "2024-07-30T12:00:00+00:00[UTC]".parse().unwrap();
+        let mut instance = Instance::create_initial_instance(VersionLevel::Minor);
+        instance.datetime = "2024-07-30T10:00:00+00:00[UTC]".parse().unwrap();
+        
+        assert_eq!(instance.age(&now), SignedDuration::from_hours(2));
+        
+        let earlier: Zoned = "2024-07-30T09:30:00+00:00[UTC]".parse().unwrap();
+        assert_eq!(instance.age(&earlier), SignedDuration::from_mins(-30));
+    }
+    
+    #[test]
+    fn test_change_note_validation() {
+        let initial = Instance::create_initial_instance(VersionLevel::Minor);
+        
+        let child = initial.create_child_instance(String::from("  Test Change \n"), VersionLevel::Patch).unwrap();
+        assert_eq!(child.get_change_note(), "Test Change");
+        
+        let child = initial.create_child_instance(String::from("   "), VersionLevel::Patch).unwrap();
+        assert_eq!(child.get_change_note(), "Instance Updated");
+        
+        let deletion = initial.create_deletion_instance(Some(String::new())).unwrap();
+        assert_eq!(deletion.get_change_note(), "Instance Deleted");
+        
+        let long_note = "a".repeat(MAX_NOTE_LENGTH + 1);
+        let result = initial.create_child_instance(long_note.clone(), VersionLevel::Patch);
+        assert!(matches!(result, Err(InstanceError::NoteTooLong(MAX_NOTE_LENGTH))));
+        assert!(initial.create_deletion_instance(Some(long_note)).is_err());
+        
+        let max_note = "é".repeat(MAX_NOTE_LENGTH);
+        assert!(initial.create_child_instance(max_note, VersionLevel::Patch).is_ok());
+        
+        assert!(matches!(validate_note("Test Change", "Default", 5), Err(InstanceError::NoteTooLong(5))));
+    }
+    
+    #[test]
+    fn test_amend_note() {
+        let initial = Instance::create_initial_instance(VersionLevel::Minor);
+        let mut child = initial.create_child_instance(String::from("Tset Change"), VersionLevel::Patch).unwrap();
+        let version = *child.get_version();
+        
+        child.amend_note(String::from(" Test Change ")).unwrap();
+        assert_eq!(child.get_change_note(), "Test Change");
+        assert_eq!(child.get_version(), &version);
+        
+        child.amend_note(String::new()).unwrap();
+        assert_eq!(child.get_change_note(), "Test Change");
+        
+        let result = child.amend_note("a".repeat(MAX_NOTE_LENGTH + 1));
+        assert!(matches!(result, Err(InstanceError::NoteTooLong(MAX_NOTE_LENGTH))));
+        assert_eq!(child.get_change_note(), "Test Change");
+    }
+    
+    #[test]
+    fn test_instance_metadata() {
+        let mut instance = Instance::create_initial_instance(VersionLevel::Minor);
+        assert_eq!(instance.get_metadata("ticket"), None);
+        
+        instance.set_metadata(String::from("ticket"), String::from("TER-12"));
+        instance.set_metadata(String::from("source"), String::from("import"));
+        assert_eq!(instance.get_metadata("ticket"), Some("TER-12"));
+        assert_eq!(instance.get_metadata("source"), Some("import"));
+        
+        instance.set_metadata(String::from("ticket"), String::from("TER-13"));
+        assert_eq!(instance.get_metadata("ticket"), Some("TER-13"));
+        
+        let child = instance.create_child_instance(String::from("Test Change"), VersionLevel::Patch).unwrap();
+        assert_eq!(child.get_metadata("ticket"), None);
+    }
+    
+    #[test]
+    fn test_instance_type_display_and_parse() {
+        let instance_types = [
+            (InstanceType::Creation, "Created"),
+            (InstanceType::Update, "Updated"),
+            (InstanceType::Deletion, "Deleted"),
+            (InstanceType::Restoration, "Restored"),
+            (InstanceType::Rename, "Renamed"),
+            (InstanceType::Touch, "Touched"),
+        ];
+        
+        for (instance_type, display) in instance_types {
+            assert_eq!(instance_type.to_string(), display);
+            assert_eq!(display.parse::<InstanceType>().unwrap(), instance_type);
+            assert_eq!(display.to_uppercase().parse::<InstanceType>().unwrap(), instance_type);
+        }
+    }
+    
+    #[test]
+    fn test_instance_type_parse_unknown() {
+        let error = "Archived".parse::<InstanceType>().unwrap_err();
+        assert!(matches!(error, InstanceError::InvalidInstanceType(_)));
+        assert_eq!(error.to_string(), "Invalid instance type: Archived");
+    }
+    
+    #[test]
+    fn test_instance_list_filter_by_type() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        
+        let deletion = instance_list.latest().unwrap().get_instance().create_deletion_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: deletion }).unwrap();
+        
+        let restoration = instance_list.latest().unwrap().get_instance().create_restoration_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: restoration }).unwrap();
+        
+        let update = instance_list.latest().unwrap().get_instance().create_child_instance(String::from("Test Change 3"), VersionLevel::Patch).unwrap();
+        instance_list.add(TestInstance { instance: update }).unwrap();
+        
+        let notes: Vec<&str> = instance_list
+            .filter_by_type(InstanceType::Update)
+            .map(|instance| instance.get_instance().get_change_note())
+            .collect();
+        assert_eq!(notes, vec!["Test Change", "Test Change 2", "Test Change 3"]);
+        
+        assert_eq!(instance_list.filter_by_type(InstanceType::Deletion).count(), 1);
+    }
+    
+    #[test]
+    fn test_instance_list_restore_without_delete() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change"]));
+        
+        let restoration = instance_list.latest().unwrap().get_instance().create_restoration_instance(None).unwrap();
+        let result = instance_list.add(TestInstance { instance: restoration });
+        assert!(matches!(result, Err(InstanceError::CannotRestoreNonDeleted)));
+        assert_eq!(instance_list.len(), 2);
+        
+        let deletion = instance_list.latest().unwrap().get_instance().create_deletion_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: deletion }).unwrap();
+        
+        let restoration = instance_list.latest().unwrap().get_instance().create_restoration_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: restoration }).unwrap();
+        assert!(!instance_list.is_deleted());
+        assert_eq!(instance_list.len(), 4);
+    }
+    
+    #[test]
+    fn test_instance_list_try_new() {
+        let instance_list = InstanceList::try_new(create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T11:00:00+00:00[UTC]",
+            "2024-07-30T11:00:00+00:00[UTC]",
+        ])).unwrap();
+        assert_eq!(instance_list.len(), 3);
+        
+        let result = InstanceList::try_new(create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T12:00:00+00:00[UTC]",
+            "2024-07-30T11:00:00+00:00[UTC]",
+        ]));
+        assert!(matches!(result, Err(InstanceError::DatetimeIncorrectlyOrdered)));
+    }
+    
+    #[test]
+    fn test_instance_list_try_new_update_after_deletion() {
+        let mut instances = create_history(&["Test Change"]);
+        let deletion = instances[1].instance.create_deletion_instance(None).unwrap();
+        let update = deletion.create_child_instance(String::from("Test Change 2"), VersionLevel::Patch).unwrap();
+        instances.push(TestInstance { instance: deletion });
+        instances.push(TestInstance { instance: update });
+        
+        let result = InstanceList::try_new(instances);
+        assert!(matches!(result, Err(InstanceError::CannotAddToDeletedInstanceList)));
+    }
+    
+    #[test]
+    fn test_instance_list_deleted_at() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change"]));
+        assert!(instance_list.deleted_at().is_none());
+        
+        let deletion = instance_list.latest().unwrap().get_instance().create_deletion_instance(None).unwrap();
+        let deletion_datetime = deletion.get_datetime().clone();
+        instance_list.add(TestInstance { instance: deletion }).unwrap();
+        assert_eq!(instance_list.deleted_at(), Some(&deletion_datetime));
+        
+        let restoration = instance_list.latest().unwrap().get_instance().create_restoration_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: restoration }).unwrap();
+        assert!(instance_list.deleted_at().is_none());
+    }
+    
+    #[test]
+    fn test_instance_list_versions() {
+        let instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        let versions = instance_list.versions();
+        
+        assert_eq!(versions, vec![Version::new(0, 1, 0), Version::new(0, 1, 1), Version::new(0, 1, 2)]);
+        assert!(versions.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+    
+    #[test]
+    fn test_instance_list_latest_of_type() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        assert!(instance_list.latest_of_type(InstanceType::Deletion).is_none());
+        
+        let deletion = instance_list.latest().unwrap().get_instance().create_deletion_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: deletion }).unwrap();
+        
+        let restoration = instance_list.latest().unwrap().get_instance().create_restoration_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: restoration }).unwrap();
+        
+        let update = instance_list.latest().unwrap().get_instance().create_child_instance(String::from("Test Change 3"), VersionLevel::Patch).unwrap();
+        instance_list.add(TestInstance { instance: update }).unwrap();
+        
+        let deletion = instance_list.latest_of_type(InstanceType::Deletion).unwrap().get_instance();
+        assert_eq!(deletion.get_version(), &Version::new(1, 0, 0));
+        
+        let update = instance_list.latest_of_type(InstanceType::Update).unwrap().get_instance();
+        assert_eq!(update.get_change_note(), "Test Change 3");
+    }
+    
+    #[test]
+    fn test_instance_list_latest_stable() {
+        let mut instances = create_history(&[]);
+        let stable = instances[0].instance.create_child_instance_with_version(String::from("Release"), Version::new(1, 2, 0)).unwrap();
+        let beta = stable.create_child_instance_with_version(String::from("Beta"), Version::new(1, 3, 0).with_pre_release("beta.1").unwrap()).unwrap();
+        let rc = beta.create_child_instance_with_version(String::from("Release candidate"), Version::new(1, 3, 0).with_pre_release("rc.1").unwrap()).unwrap();
+        instances.extend([stable, beta, rc].into_iter().map(|instance| TestInstance { instance }));
+        let instance_list = InstanceList::new(instances);
+        
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_version().to_string(), "1.3.0-rc.1");
+        assert_eq!(instance_list.latest_stable().unwrap().get_instance().get_version(), &Version::new(1, 2, 0));
+        
+        let empty: InstanceList<TestInstance> = InstanceList::new(Vec::new());
+        assert!(empty.latest_stable().is_none());
+    }
+    
+    #[test]
+    fn test_instance_list_add_equal_datetime() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change"]));
+        let latest = instance_list.latest().unwrap().get_instance();
+        
+        let mut instance = latest.create_child_instance(String::from("Test Change 2"), VersionLevel::Patch).unwrap();
+        instance.datetime = latest.datetime.clone();
+        
+        instance_list.add(TestInstance { instance }).unwrap();
+        assert_eq!(instance_list.len(), 3);
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_change_note(), "Test Change 2");
+    }
+    
+    #[test]
+    fn test_instance_list_add_earlier_datetime() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change"]));
+        let latest = instance_list.latest().unwrap().get_instance();
+        
+        let mut instance = latest.create_child_instance(String::from("Test Change 2"), VersionLevel::Patch).unwrap();
+        instance.datetime = latest.datetime.checked_sub(jiff::Span::new().seconds(1)).unwrap();
+        
+        assert!(matches!(instance_list.add(TestInstance { instance }), Err(InstanceError::DatetimeIncorrectlyOrdered)));
+        assert_eq!(instance_list.len(), 2);
+    }
+    
+    #[test]
+    fn test_instance_list_squash_updates() {
+        let history = create_history(&["Test Change", "Test Change 2", "Test Change 3"]);
+        let first_update_datetime = history[1].get_instance().get_datetime().clone();
+        let mut instance_list = InstanceList::new(history);
+        
+        instance_list.squash_updates(String::from("Combined Change")).unwrap();
+        
+        assert_eq!(instance_list.len(), 2);
+        let squashed = instance_list.latest().unwrap().get_instance();
+        assert_eq!(squashed.get_change_note(), "Combined Change");
+        assert_eq!(squashed.get_version(), &Version::new(0, 1, 3));
+        assert_eq!(squashed.get_datetime(), &first_update_datetime);
+        assert!(squashed.is_type_of(InstanceType::Update));
+    }
+    
+    #[test]
+    fn test_instance_list_squash_updates_validates_note() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change", "Test Change 2"]));
+        
+        let result = instance_list.squash_updates("a".repeat(MAX_NOTE_LENGTH + 1));
+        assert!(matches!(result, Err(InstanceError::NoteTooLong(_))));
+        assert_eq!(instance_list.len(), 3);
+        
+        instance_list.squash_updates(String::from("  ")).unwrap();
+        assert_eq!(instance_list.len(), 2);
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_change_note(), "Instance Updated");
+    }
+    
+    #[test]
+    fn test_instance_list_squash_updates_stops_at_restoration() {
+        let mut instance_list = InstanceList::new(create_history(&["Test Change"]));
+        
+        let deletion = instance_list.latest().unwrap().get_instance().create_deletion_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: deletion }).unwrap();
+        
+        let restoration = instance_list.latest().unwrap().get_instance().create_restoration_instance(None).unwrap();
+        instance_list.add(TestInstance { instance: restoration }).unwrap();
+        
+        for note in ["Test Change 2", "Test Change 3"] {
+            let update = instance_list.latest().unwrap().get_instance().create_child_instance(note.to_string(), VersionLevel::Patch).unwrap();
+            instance_list.add(TestInstance { instance: update }).unwrap();
+        }
+        
+        instance_list.squash_updates(String::from("Combined Change")).unwrap();
+        
+        assert_eq!(instance_list.len(), 5);
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_version(), &Version::new(2, 0, 2));
+        assert_eq!(instance_list.count_by_type(InstanceType::Update), 2);
+        
+        instance_list.squash_updates(String::from("Nothing to squash")).unwrap();
+        assert_eq!(instance_list.len(), 5);
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_change_note(), "Combined Change");
+    }
+    
+    #[test]
+    fn test_instance_list_merge() {
+        let mut instance_list = InstanceList::new(create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T12:00:00+00:00[UTC]",
+        ]));
+        let other = InstanceList::new(create_history_at(&[
+            "2024-07-30T11:00:00+00:00[UTC]",
+            "2024-07-30T13:00:00+00:00[UTC]",
+        ]));
+        
+        instance_list.merge(&other).unwrap();
+        
+        assert_eq!(instance_list.len(), 4);
+        assert_eq!(instance_list.count_by_type(InstanceType::Creation), 1);
+        assert!(instance_list.earliest().unwrap().get_instance().is_type_of(InstanceType::Creation));
+        
+        let versions: Vec<Version> = instance_list.iter().map(|value| *value.get_instance().get_version()).collect();
+        assert_eq!(versions, vec![Version::new(0, 1, 0), Version::new(1, 0, 0), Version::new(1, 0, 1), Version::new(1, 0, 2)]);
+    }
+    
+    #[test]
+    fn test_instance_list_truncate_before() {
+        let mut instance_list = InstanceList::new(create_history_at(&[
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T11:00:00+00:00[UTC]",
+            "2024-07-30T12:00:00+00:00[UTC]",
+            "2024-07-30T13:00:00+00:00[UTC]",
+        ]));
+        
+        let cutoff: Zoned = "2024-07-30T11:30:00+00:00[UTC]".parse().unwrap();
+        assert_eq!(instance_list.truncate_before(&cutoff), 2);
+        assert_eq!(instance_list.len(), 2);
+        assert_eq!(instance_list.earliest().unwrap().get_instance().get_datetime(), &"2024-07-30T12:00:00+00:00[UTC]".parse::<Zoned>().unwrap());
+        
+        let cutoff: Zoned = "2024-07-31T00:00:00+00:00[UTC]".parse().unwrap();
+        assert_eq!(instance_list.truncate_before(&cutoff), 1);
+        assert_eq!(instance_list.len(), 1);
+        assert_eq!(instance_list.truncate_before(&cutoff), 0);
+        assert_eq!(instance_list.latest().unwrap().get_instance().get_datetime(), &"2024-07-30T13:00:00+00:00[UTC]".parse::<Zoned>().unwrap());
+    }
+    
+    #[test]
+    fn test_instance_list_truncate_before_keeps_deletion_of_restoration() {
+        let mut instances = create_history(&["Test Change"]);
+        let deletion = instances[1].instance.create_deletion_instance(None).unwrap();
+        let restoration = deletion.create_restoration_instance(None).unwrap();
+        instances.push(TestInstance { instance: deletion });
+        instances.push(TestInstance { instance: restoration });
+        
+        let datetimes = [
+            "2024-07-30T10:00:00+00:00[UTC]",
+            "2024-07-30T11:00:00+00:00[UTC]",
+            "2024-07-30T12:00:00+00:00[UTC]",
+            "2024-07-30T13:00:00+00:00[UTC]",
+        ];
+        for (instance, datetime) in instances.iter_mut().zip(datetimes) {
+            instance.instance.datetime = datetime.parse().unwrap();
+        }
+        let mut instance_list = InstanceList::try_new(instances).unwrap();
+        
+        let cutoff: Zoned = "2024-07-30T12:30:00+00:00[UTC]".parse().unwrap();
+        assert_eq!(instance_list.truncate_before(&cutoff), 2);
+        assert_eq!(instance_list.len(), 2);
+        assert!(instance_list.earliest().unwrap().get_instance().is_type_of(InstanceType::Deletion));
+        assert!(!instance_list.is_deleted());
+        
+        let remaining: Vec<TestInstance> = instance_list.iter().cloned().collect();
+        assert!(InstanceList::try_new(remaining).is_ok());
+    }
 }
\ No newline at end of file
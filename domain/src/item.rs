@@ -1,10 +1,23 @@
-use uuid::Uuid;
-use crate::file_name::FileName;
-use crate::instance::{Instance, Instanced, InstanceError, InstanceList};
+use jiff::{SignedDuration, Zoned};
+use crate::file_name::{FileName, FileNameError};
+use crate::id::{IdGenerator, UuidGenerator};
+use crate::instance::{Instance, Instanced, InstancedMut, InstanceError, InstanceList, InstanceType};
 use crate::tag::{Tag, TagError};
-use crate::version::VersionLevel;
+use crate::version::{Version, VersionLevel};
 
-struct Item {
+/// A versioned file tracked by its containing folder, extension and instance history.
+///
+/// ```
+/// use domain::item::{FileType, Item};
+///
+/// let item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image).unwrap();
+/// assert_eq!(item.get_folder(), "res/files");
+/// assert_eq!(item.get_extension(), "jpeg");
+/// assert_eq!(item.get_title(), None);
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Item {
     id: String,
     instances: InstanceList<ItemInstance>,
     containing_folder: String,
@@ -16,12 +29,23 @@ struct Item {
 
 impl Item {
     pub fn new(containing_folder: String, file_extension: String, file_type: FileType) -> Result<Self, ItemError> {
-        if containing_folder.ends_with('/') {
-            return Err(ItemError::FilePath(String::from("Folder path cannot end with a slash")));
-        }
+        Self::new_with(containing_folder, file_extension, file_type, &UuidGenerator)
+    }
+    
+    /// Like `new`, but takes the item's id and the id of its first instance from `generator`.
+    /// Instances recorded later still get random ids.
+    pub fn new_with(containing_folder: String, file_extension: String, file_type: FileType, generator: &dyn IdGenerator) -> Result<Self, ItemError> {
+        validate_folder(&containing_folder)?;
+        let file_extension = normalize_extension(file_extension)?;
+        
+        let id = generator.generate();
+        let mut initial_instance = ItemInstance::new_with(generator);
+        initial_instance.folder = Some(containing_folder.clone());
+        initial_instance.extension = Some(file_extension.clone());
+        
         Ok(Self {
-            id: Uuid::new_v4().to_string(),
-            instances: InstanceList::new(Vec::from([ItemInstance::new()])),
+            id,
+            instances: InstanceList::new(Vec::from([initial_instance])),
             containing_folder,
             file_extension,
             file_type,
@@ -30,21 +54,146 @@ impl Item {
         })
     }
     
-    pub fn edit_title(&mut self, title: String) {
+    pub fn builder() -> ItemBuilder {
+        ItemBuilder::default()
+    }
+    
+    /// Creates an item whose file type is derived from the extension.
+    pub fn new_auto(containing_folder: String, file_extension: String) -> Result<Self, ItemError> {
+        let file_type = FileType::from_extension(&file_extension);
+        Self::new(containing_folder, file_extension, file_type)
+    }
+    
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+    
+    /// Copies the item, including its instance history and tags, under a fresh id.
+    pub fn duplicate(&self) -> Item {
+        Item {
+            id: UuidGenerator.generate(),
+            ..self.clone()
+        }
+    }
+    
+    pub fn get_folder(&self) -> &str {
+        &self.containing_folder
+    }
+    
+    pub fn get_extension(&self) -> &str {
+        &self.file_extension
+    }
+    
+    pub fn get_file_type(&self) -> FileType {
+        self.file_type
+    }
+    
+    pub fn get_title(&self) -> Option<&str> {
+        self.file_title.as_deref()
+    }
+    
+    /// Changes the title, recording a rename instance. The note defaults to
+    /// "Title changed to {title}".
+    pub fn edit_title(&mut self, title: String, note: Option<String>) -> Result<(), ItemError> {
+        let item_instance = match self.instances.latest() {
+            Some(instance) => instance,
+            None => return Err(ItemError::EditEmptyItem),
+        };
+        
+        let note = note.unwrap_or_else(|| format!("Title changed to {}", title));
+        let new_instance = item_instance.get_instance().create_rename_instance(note)?;
+        self.add_instance(ItemInstance::from_previous(item_instance, item_instance.file_name.clone(), new_instance))?;
         self.file_title = Some(title);
+        
+        Ok(())
+    }
+
+    /// Like `edit_title`, but returns the item's path before and after the rename. The
+    /// title isn't part of the path and a title change keeps the file name, so the two paths
+    /// are always equal.
+    pub fn rename_title(&mut self, title: String, note: Option<String>) -> Result<(String, String), ItemError> {
+        let old_path = self.current_file_path()?;
+        self.edit_title(title, note)?;
+        let new_path = self.current_file_path()?;
+        
+        Ok((old_path, new_path))
     }
 
     pub fn edit(&mut self, note: String, version_level: VersionLevel) -> Result<(), ItemError> {
+        self.add_edit_instance(note, version_level, None, None)
+    }
+
+    /// Records an update instance with the given version, e.g. when importing from another
+    /// system. The version must be newer than the current version.
+    pub fn set_version(&mut self, target: Version, note: String) -> Result<(), ItemError> {
+        let item_instance = match self.instances.latest() {
+            Some(instance) => instance,
+            None => return Err(ItemError::EditEmptyItem),
+        };
+        
+        let new_instance = item_instance.get_instance().create_child_instance_with_version(note, target)?;
+        self.add_instance(ItemInstance::with_instance(FileName::new(*new_instance.get_version()), new_instance))?;
+        
+        Ok(())
+    }
+    
+    /// Edits the item, recording the caller-computed hash of the new content on the new
+    /// instance. The hash must be a non-empty hex string if present.
+    pub fn edit_with_hash(&mut self, note: String, version_level: VersionLevel, hash: Option<String>) -> Result<(), ItemError> {
+        if let Some(hash) = &hash {
+            validate_hash(hash)?;
+        }
+        
+        self.add_edit_instance(note, version_level, hash, None)
+    }
+    
+    /// Edits the item, recording the size of the new content in bytes on the new instance.
+    pub fn edit_with_size(&mut self, note: String, version_level: VersionLevel, size: u64) -> Result<(), ItemError> {
+        self.add_edit_instance(note, version_level, None, Some(size))
+    }
+    
+    fn add_edit_instance(&mut self, note: String, version_level: VersionLevel, hash: Option<String>, size: Option<u64>) -> Result<(), ItemError> {
         let item_instance = match self.instances.latest() {
             Some(instance) => instance,
             None => return Err(ItemError::EditEmptyItem),
         };
 
-        let new_instance = item_instance.get_instance().create_child_instance(note, version_level);
-        self.instances.add(ItemInstance::with_instance(FileName::new(new_instance.get_version().clone()), new_instance))?;
+        let new_instance = item_instance.get_instance().create_child_instance(note, version_level)?;
+        let mut new_item_instance = ItemInstance::with_instance(FileName::new(*new_instance.get_version()), new_instance);
+        new_item_instance.content_hash = hash;
+        new_item_instance.size_bytes = size;
+        self.add_instance(new_item_instance)?;
 
         Ok(())
     }
+    
+    /// The content hash recorded on the latest instance, if any.
+    pub fn current_hash(&self) -> Option<&str> {
+        self.instances.latest()?.get_content_hash()
+    }
+    
+    /// The size in bytes recorded on the latest instance, if any.
+    pub fn current_size(&self) -> Option<u64> {
+        self.instances.latest()?.get_size_bytes()
+    }
+    
+    /// Sums the known sizes of the distinct files in the history. Instances that reuse an
+    /// earlier file, such as touches and restorations, aren't counted again.
+    pub fn total_size_across_versions(&self) -> u64 {
+        let mut counted: Vec<&FileName> = Vec::new();
+        let mut total = 0;
+        
+        for instance in self.instances.iter() {
+            if let Some(size) = instance.size_bytes {
+                if !counted.contains(&&instance.file_name) {
+                    counted.push(&instance.file_name);
+                    total += size;
+                }
+            }
+        }
+        
+        total
+    }
 
     pub fn delete(&mut self, note: Option<String>) -> Result<(), ItemError> {
         let item_instance = match self.instances.latest() {
@@ -52,8 +201,8 @@ impl Item {
             None => return Err(ItemError::EditEmptyItem),
         };
 
-        let new_instance = item_instance.get_instance().create_deletion_instance(note);
-        self.instances.add(ItemInstance::with_instance(item_instance.file_name.clone(), new_instance))?;
+        let new_instance = item_instance.get_instance().create_deletion_instance(note)?;
+        self.add_instance(ItemInstance::from_previous(item_instance, item_instance.file_name.clone(), new_instance))?;
 
         Ok(())
     }
@@ -64,14 +213,154 @@ impl Item {
             None => return Err(ItemError::EditEmptyItem),
         };
 
-        let new_instance = item_instance.get_instance().create_restoration_instance(note);
-        self.instances.add(ItemInstance::with_instance(item_instance.file_name.clone(), new_instance))?;
+        let new_instance = item_instance.get_instance().create_restoration_instance(note)?;
+        self.add_instance(ItemInstance::from_previous(item_instance, item_instance.file_name.clone(), new_instance))?;
+
+        Ok(())
+    }
+
+    /// Restores a deleted item to the file of an earlier version, recording a restoration
+    /// instance that reuses that version's file name, content hash, folder and extension.
+    /// The item's folder and extension are set back to that version's.
+    pub fn restore_to(&mut self, version: &Version, note: Option<String>) -> Result<(), ItemError> {
+        let item_instance = match self.instances.latest() {
+            Some(instance) => instance,
+            None => return Err(ItemError::EditEmptyItem),
+        };
+        
+        if !self.instances.is_deleted() {
+            return Err(ItemError::Instance(InstanceError::CannotRestoreNonDeleted));
+        }
+        
+        let target = match self.instances.get_by_version(version) {
+            Some(instance) => instance,
+            None => return Err(ItemError::VersionNotFound(version.to_string())),
+        };
+        
+        let note = note.unwrap_or_else(|| format!("Restored to version {}", version));
+        let new_instance = item_instance.get_instance().create_restoration_instance(Some(note))?;
+        let mut new_item_instance = ItemInstance::with_instance(target.file_name.clone(), new_instance);
+        new_item_instance.content_hash = target.content_hash.clone();
+        new_item_instance.size_bytes = target.size_bytes;
+        new_item_instance.folder = target.folder.clone();
+        new_item_instance.extension = target.extension.clone();
+        let (folder, extension) = (target.folder.clone(), target.extension.clone());
+        self.add_instance(new_item_instance)?;
+        
+        if let Some(folder) = folder {
+            self.containing_folder = folder;
+        }
+        
+        if let Some(extension) = extension {
+            self.file_extension = extension;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the item to another folder, recording an update instance noting the move. The
+    /// file itself is unchanged, so the new instance keeps the current file name.
+    pub fn move_to_folder(&mut self, new_folder: String) -> Result<(), ItemError> {
+        validate_folder(&new_folder)?;
+        
+        let item_instance = match self.instances.latest() {
+            Some(instance) => instance,
+            None => return Err(ItemError::EditEmptyItem),
+        };
+        
+        let note = format!("Moved from {} to {}", self.containing_folder, new_folder);
+        let new_instance = item_instance.get_instance().create_child_instance(note, VersionLevel::Patch)?;
+        let mut new_item_instance = ItemInstance::from_previous(item_instance, item_instance.file_name.clone(), new_instance);
+        new_item_instance.folder = Some(new_folder.clone());
+        self.add_instance(new_item_instance)?;
+        self.containing_folder = new_folder;
+        
+        Ok(())
+    }
+
+    /// Records a metadata-only touch that keeps the current version, file name and content
+    /// hash, so the item's path is unchanged. `get_by_version` finds the earliest instance
+    /// when several share a version.
+    pub fn touch(&mut self, note: String) -> Result<(), ItemError> {
+        let item_instance = match self.instances.latest() {
+            Some(instance) => instance,
+            None => return Err(ItemError::EditEmptyItem),
+        };
+        
+        let new_instance = item_instance.get_instance().create_touch_instance(note)?;
+        self.add_instance(ItemInstance::from_previous(item_instance, item_instance.file_name.clone(), new_instance))?;
+        
+        Ok(())
+    }
 
+    /// Changes the file extension, e.g. after re-encoding the file, recording a rename
+    /// instance with a new file name. The new file's content hash and size are unknown.
+    pub fn change_extension(&mut self, new_extension: String, note: String) -> Result<(), ItemError> {
+        let new_extension = normalize_extension(new_extension)?;
+        
+        let item_instance = match self.instances.latest() {
+            Some(instance) => instance,
+            None => return Err(ItemError::EditEmptyItem),
+        };
+        
+        let new_instance = item_instance.get_instance().create_rename_instance(note)?;
+        let mut new_item_instance = ItemInstance::with_instance(FileName::new(*new_instance.get_version()), new_instance);
+        new_item_instance.extension = Some(new_extension.clone());
+        self.add_instance(new_item_instance)?;
+        self.file_extension = new_extension;
+        
         Ok(())
     }
 
-    pub fn add_tag(&mut self, tag: Tag) {
+    /// Adds a tag, rejecting it if a tag with the same id is already on the item. Use
+    /// `add_unique_tag` to also reject duplicate values.
+    pub fn add_tag(&mut self, tag: Tag) -> Result<(), ItemError> {
+        self.check_duplicate_tag(&tag)?;
+        self.tags.push(tag);
+        
+        Ok(())
+    }
+    
+    /// Adds all of the tags, or none of them if any has the same id as an existing tag or as
+    /// another tag in the batch.
+    pub fn add_tags(&mut self, tags: Vec<Tag>) -> Result<(), ItemError> {
+        for (index, tag) in tags.iter().enumerate() {
+            self.check_duplicate_tag(tag)?;
+            
+            if tags[..index].iter().any(|other| other.get_id() == tag.get_id()) {
+                return Err(ItemError::DuplicateTag(tag.get_id().to_string()));
+            }
+        }
+        
+        self.tags.extend(tags);
+        
+        Ok(())
+    }
+    
+    /// Like `add_tag`, but also rejects the tag with `DuplicateTagValue` if its value matches
+    /// (see `Tag::value_matches`) that of a tag on the item. Deleted tags on the item are
+    /// ignored.
+    pub fn add_unique_tag(&mut self, tag: Tag) -> Result<(), ItemError> {
+        self.check_duplicate_tag(&tag)?;
+        
+        let value = tag.get_value()?;
+        for other in self.tags.iter().filter(|other| !other.is_deleted()) {
+            if tag.value_matches(&other.get_value()?) {
+                return Err(ItemError::DuplicateTagValue(value));
+            }
+        }
+        
         self.tags.push(tag);
+        
+        Ok(())
+    }
+    
+    fn check_duplicate_tag(&self, tag: &Tag) -> Result<(), ItemError> {
+        if self.has_tag(tag.get_id()) {
+            return Err(ItemError::DuplicateTag(tag.get_id().to_string()));
+        }
+        
+        Ok(())
     }
     
     pub fn remove_tag(&mut self, tag_id: &str) -> Result<(), ItemError> {
@@ -86,101 +375,573 @@ impl Item {
         }
     }
     
+    /// Removes the tags with the given ids, returning how many were removed. Ids that aren't
+    /// on the item are ignored.
+    pub fn remove_tags(&mut self, ids: &[&str]) -> Result<usize, ItemError> {
+        let tag_count = self.tags.len();
+        self.tags.retain(|tag| !ids.contains(&tag.get_id()));
+        
+        Ok(tag_count - self.tags.len())
+    }
+    
+    pub fn get_tags(&self) -> &[Tag] {
+        &self.tags
+    }
+    
+    /// The current values of the item's tags, optionally including deleted tags.
+    pub fn tag_values(&self, include_deleted: bool) -> Result<Vec<String>, ItemError> {
+        let mut values = Vec::new();
+        for tag in &self.tags {
+            if include_deleted || !tag.is_deleted() {
+                values.push(tag.get_value()?);
+            }
+        }
+        
+        Ok(values)
+    }
+    
+    pub fn has_tag(&self, tag_id: &str) -> bool {
+        self.tags.iter().any(|tag| tag.get_id() == tag_id)
+    }
+    
+    /// Finds the first tag whose current value equals the given value.
+    pub fn find_tag_by_value(&self, value: &str) -> Option<&Tag> {
+        self.tags.iter().find(|tag| tag.get_value().is_ok_and(|tag_value| tag_value == value))
+    }
+    
+    /// Finds the first tag matching `value` case-insensitively; see `Tag::value_matches`.
+    pub fn find_tag_by_value_ci(&self, value: &str) -> Option<&Tag> {
+        self.tags.iter().find(|tag| tag.value_matches(value))
+    }
+    
+    /// The MIME type of the item, taken from the extension where it is known and from the
+    /// file type otherwise.
+    pub fn mime_type(&self) -> &'static str {
+        mime_type_for_extension(&self.file_extension).unwrap_or_else(|| self.file_type.mime_type())
+    }
+    
+    pub fn current_version(&self) -> Result<&Version, ItemError> {
+        match self.instances.latest() {
+            Some(instance) => Ok(instance.get_instance().get_version()),
+            None => Err(ItemError::RetrieveEmptyItem),
+        }
+    }
+    
+    /// The version an edit at `level` would produce, without recording anything.
+    pub fn preview_next_version(&self, level: VersionLevel) -> Result<Version, ItemError> {
+        Ok(self.current_version()?.create_child_version(level))
+    }
+    
+    /// When the item was created, i.e. the datetime of its earliest instance.
+    pub fn created_at(&self) -> Result<&Zoned, ItemError> {
+        match self.instances.earliest() {
+            Some(instance) => Ok(instance.get_instance().get_datetime()),
+            None => Err(ItemError::RetrieveEmptyItem),
+        }
+    }
+    
+    /// When the item was last changed, i.e. the datetime of its latest instance.
+    pub fn modified_at(&self) -> Result<&Zoned, ItemError> {
+        match self.instances.latest() {
+            Some(instance) => Ok(instance.get_instance().get_datetime()),
+            None => Err(ItemError::RetrieveEmptyItem),
+        }
+    }
+    
+    pub fn is_deleted(&self) -> bool {
+        self.instances.is_deleted()
+    }
+    
+    /// Whether the item has been deleted at any point, even if it was later restored.
+    pub fn was_ever_deleted(&self) -> bool {
+        self.instances.iter().any(|instance| instance.get_instance().is_type_of(InstanceType::Deletion))
+    }
+    
+    /// The number of content edits, i.e. update instances. Creation, deletion, restoration,
+    /// rename and touch instances aren't counted.
+    pub fn edit_count(&self) -> usize {
+        self.instances.count_by_type(InstanceType::Update)
+    }
+    
+    pub fn total_instances(&self) -> usize {
+        self.instances.len()
+    }
+    
+    pub fn latest_file_name(&self) -> Result<&FileName, ItemError> {
+        match self.instances.latest() {
+            Some(instance) => Ok(instance.get_file_name()),
+            None => Err(ItemError::RetrieveEmptyItem),
+        }
+    }
+    
     pub fn current_file_path(&self) -> Result<String, ItemError> {
         let instance = match self.instances.latest() {
             Some(instance) => instance,
             None => return Err(ItemError::RetrieveEmptyItem),
         };
         
-        Ok(format!("{}/{}.{}", self.containing_folder, instance.file_name.to_string().unwrap(), self.file_extension))
+        Ok(self.file_path_for(instance))
+    }
+    
+    /// The path of each instance in order, skipping consecutive duplicates (e.g. deletions,
+    /// which reuse the previous file name). Each path uses the folder and extension the
+    /// instance was recorded with.
+    pub fn all_file_paths(&self) -> Result<Vec<String>, ItemError> {
+        if self.instances.is_empty() {
+            return Err(ItemError::RetrieveEmptyItem);
+        }
+        
+        let mut paths: Vec<String> = self.instances.iter().map(|instance| self.file_path_for(instance)).collect();
+        paths.dedup();
+        
+        Ok(paths)
+    }
+    
+    /// Adds an instance, recording the current folder and extension on it unless it
+    /// already has its own.
+    fn add_instance(&mut self, mut item_instance: ItemInstance) -> Result<(), ItemError> {
+        item_instance.folder.get_or_insert_with(|| self.containing_folder.clone());
+        item_instance.extension.get_or_insert_with(|| self.file_extension.clone());
+        self.instances.add(item_instance)?;
+        
+        Ok(())
+    }
+    
+    /// Instances without a recorded folder or extension, e.g. from data persisted before
+    /// they were tracked, fall back to the item's current ones.
+    fn file_path_for(&self, instance: &ItemInstance) -> String {
+        let folder = instance.folder.as_deref().unwrap_or(&self.containing_folder);
+        let extension = instance.extension.as_deref().unwrap_or(&self.file_extension);
+        format!("{}/{}.{}", folder, instance.file_name, extension)
     }
 }
 
-#[derive(Debug)]
-pub enum ItemError {
-    TagNotFound,
-    EditEmptyItem,
-    RetrieveEmptyItem,
-    FilePath(String),
-    Instance(InstanceError),
-    Tag(TagError),
+/// Items are compared by identity: two items are equal when they have the same id, even if
+/// their histories differ.
+impl PartialEq for Item {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
 }
 
-impl std::error::Error for ItemError {}
+impl Eq for Item {}
 
-impl From<InstanceError> for ItemError {
-    fn from(e: InstanceError) -> ItemError {
-        ItemError::Instance(e)
+impl std::hash::Hash for Item {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
     }
 }
 
-impl From<TagError> for ItemError {
-    fn from(e: TagError) -> ItemError {
-        ItemError::Tag(e)
-    }
+/// Builds an `Item` with its title and tags set before the first instance is recorded. The
+/// file type is derived from the extension when not given.
+///
+/// ```
+/// use domain::item::Item;
+/// use domain::tag::Tag;
+///
+/// let item = Item::builder()
+///     .folder("res/files")
+///     .extension("png")
+///     .title("Holiday")
+///     .tag(Tag::new(String::from("beach")))
+///     .build()
+///     .unwrap();
+/// assert_eq!(item.get_title(), Some("Holiday"));
+/// ```
+#[derive(Default)]
+pub struct ItemBuilder {
+    containing_folder: Option<String>,
+    file_extension: Option<String>,
+    file_type: Option<FileType>,
+    file_title: Option<String>,
+    tags: Vec<Tag>,
 }
 
-impl std::fmt::Display for ItemError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            ItemError::Instance(e) => write!(f, "Item instance error: {}", e),
-            ItemError::Tag(e) => write!(f, "Item tag error: {}", e),
-            ItemError::TagNotFound => write!(f, "Tag not found"),
-            ItemError::EditEmptyItem => write!(f, "Cannot edit an empty item"),
-            ItemError::RetrieveEmptyItem => write!(f, "Cannot retrieve an empty item"),
-            ItemError::FilePath(e) => write!(f, "Path error: {}", e),
+impl ItemBuilder {
+    pub fn folder(mut self, folder: impl Into<String>) -> Self {
+        self.containing_folder = Some(folder.into());
+        self
+    }
+    
+    pub fn extension(mut self, extension: impl Into<String>) -> Self {
+        self.file_extension = Some(extension.into());
+        self
+    }
+    
+    pub fn file_type(mut self, file_type: FileType) -> Self {
+        self.file_type = Some(file_type);
+        self
+    }
+    
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.file_title = Some(title.into());
+        self
+    }
+    
+    pub fn tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+    
+    pub fn build(self) -> Result<Item, ItemError> {
+        let containing_folder = match self.containing_folder {
+            Some(folder) => folder,
+            None => return Err(ItemError::FilePath(String::from("Folder path is required"))),
+        };
+        
+        let file_extension = match self.file_extension {
+            Some(extension) => extension,
+            None => return Err(ItemError::FilePath(String::from("File extension is required"))),
+        };
+        
+        let file_type = self.file_type.unwrap_or_else(|| FileType::from_extension(&file_extension));
+        
+        let mut item = Item::new(containing_folder, file_extension, file_type)?;
+        item.file_title = self.file_title;
+        for tag in self.tags {
+            item.add_tag(tag)?;
         }
+        
+        Ok(item)
+    }
+}
+
+/// Splits a path produced by `Item::current_file_path` into its folder, file name and
+/// extension. Only the last `.` separates the extension, since pre-release versions in the
+/// file name may contain dots.
+pub fn parse_file_path(path: &str) -> Result<(String, FileName, String), ItemError> {
+    let (folder, file) = match path.rsplit_once('/') {
+        Some(parts) => parts,
+        None => return Err(ItemError::FilePath(format!("Path has no folder: {}", path))),
+    };
+    
+    validate_folder(folder)?;
+    
+    let (file_name, extension) = match file.rsplit_once('.') {
+        Some(parts) => parts,
+        None => return Err(ItemError::FilePath(format!("Path has no file extension: {}", path))),
+    };
+    
+    let extension = normalize_extension(String::from(extension))?;
+    let file_name = FileName::from_string(file_name)?;
+    
+    Ok((String::from(folder), file_name, extension))
+}
+
+fn validate_hash(hash: &str) -> Result<(), ItemError> {
+    if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(ItemError::InvalidHash(String::from(hash)));
+    }
+    
+    Ok(())
+}
+
+/// Strips a leading `.` from the extension, lower-cases it and rejects extensions that
+/// would produce a malformed path.
+fn normalize_extension(extension: String) -> Result<String, ItemError> {
+    let extension = match extension.strip_prefix('.') {
+        Some(stripped) => stripped.to_lowercase(),
+        None => extension.to_lowercase(),
+    };
+    
+    if extension.is_empty() {
+        return Err(ItemError::FilePath(String::from("File extension cannot be empty")));
+    }
+    
+    if extension.contains(['.', '/', '\\']) || extension.contains(char::is_whitespace) {
+        return Err(ItemError::FilePath(format!("File extension cannot contain dots, slashes or whitespace: {}", extension)));
+    }
+    
+    Ok(extension)
+}
+
+fn validate_folder(folder: &str) -> Result<(), ItemError> {
+    if folder.is_empty() {
+        return Err(ItemError::FilePath(String::from("Folder path cannot be empty")));
+    }
+    
+    if folder.ends_with('/') {
+        return Err(ItemError::FilePath(String::from("Folder path cannot end with a slash")));
     }
+    
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ItemError {
+    #[error("Tag not found")]
+    TagNotFound,
+    #[error("Tag already exists on item: {0}")]
+    DuplicateTag(String),
+    #[error("A tag with the value {0} already exists on item")]
+    DuplicateTagValue(String),
+    #[error("Cannot edit an empty item")]
+    EditEmptyItem,
+    #[error("Cannot retrieve an empty item")]
+    RetrieveEmptyItem,
+    #[error("Path error: {0}")]
+    FilePath(String),
+    #[error("Item file name error: {0}")]
+    FileName(#[from] FileNameError),
+    #[error("Invalid content hash: {0}")]
+    InvalidHash(String),
+    #[error("No instance with version {0}")]
+    VersionNotFound(String),
+    #[error("Item instance error: {0}")]
+    Instance(#[from] InstanceError),
+    #[error("Item tag error: {0}")]
+    Tag(#[from] TagError),
 }
 
-struct ItemInstance {
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ItemInstance {
     id: String,
     file_name: FileName,
     instance_meta: Instance,
+    #[cfg_attr(feature = "serde", serde(default))]
+    content_hash: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    size_bytes: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    folder: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    extension: Option<String>,
 }
 
 impl ItemInstance {
     pub fn new() -> Self {
+        Self::new_with(&UuidGenerator)
+    }
+    
+    pub fn new_with(generator: &dyn IdGenerator) -> Self {
         let instance = Instance::create_initial_instance(VersionLevel::Minor);
         Self {
-            id: Uuid::new_v4().to_string(),
-            file_name: FileName::new(instance.get_version().clone()),
+            id: generator.generate(),
+            file_name: FileName::new(*instance.get_version()),
             instance_meta: Instance::create_initial_instance(VersionLevel::Minor),
+            content_hash: None,
+            size_bytes: None,
+            folder: None,
+            extension: None,
         }
     }
 
     pub fn with_instance(file_name: FileName, instance: Instance) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: UuidGenerator.generate(),
             file_name,
             instance_meta: instance,
+            content_hash: None,
+            size_bytes: None,
+            folder: None,
+            extension: None,
         }
     }
-}
-
-impl Instanced for ItemInstance {
-    fn get_instance(&self) -> &Instance {
-        &self.instance_meta
+    
+    /// Like `with_instance`, but keeps the content hash and size of `previous`, for
+    /// instances that don't change the content.
+    fn from_previous(previous: &ItemInstance, file_name: FileName, instance: Instance) -> Self {
+        Self {
+            content_hash: previous.content_hash.clone(),
+            size_bytes: previous.size_bytes,
+            ..Self::with_instance(file_name, instance)
+        }
     }
-}
-
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
-enum FileType {
-    Image,
-    Video,
-    Audio,
-    Binary,
-    Document,
-    CodeFile,
+    
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+    
+    pub fn get_file_name(&self) -> &FileName {
+        &self.file_name
+    }
+    
+    pub fn get_content_hash(&self) -> Option<&str> {
+        self.content_hash.as_deref()
+    }
+    
+    pub fn get_size_bytes(&self) -> Option<u64> {
+        self.size_bytes
+    }
+    
+    /// The folder the item was in when this instance was recorded, if known.
+    pub fn get_folder(&self) -> Option<&str> {
+        self.folder.as_deref()
+    }
+    
+    /// The item's extension when this instance was recorded, if known.
+    pub fn get_extension(&self) -> Option<&str> {
+        self.extension.as_deref()
+    }
+    
+    /// Compares two instances ignoring their generated ids.
+    pub fn content_eq(&self, other: &ItemInstance) -> bool {
+        self.file_name == other.file_name
+            && self.content_hash == other.content_hash
+            && self.size_bytes == other.size_bytes
+            && self.folder == other.folder
+            && self.extension == other.extension
+            && self.instance_meta.content_eq(&other.instance_meta)
+    }
+    
+    /// Describes what changed from this instance to `other`.
+    pub fn diff(&self, other: &ItemInstance) -> InstanceDiff {
+        InstanceDiff {
+            version_change: self.instance_meta.get_version().diff(other.instance_meta.get_version()),
+            elapsed: self.instance_meta.get_datetime().duration_until(other.instance_meta.get_datetime()),
+            file_name_changed: self.file_name != other.file_name,
+        }
+    }
+}
+
+impl Default for ItemInstance {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Instanced for ItemInstance {
+    fn get_instance(&self) -> &Instance {
+        &self.instance_meta
+    }
+}
+
+impl InstancedMut for ItemInstance {
+    fn get_instance_mut(&mut self) -> &mut Instance {
+        &mut self.instance_meta
+    }
+}
+
+/// The changes between two item instances; see `ItemInstance::diff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceDiff {
+    version_change: Option<VersionLevel>,
+    elapsed: SignedDuration,
+    file_name_changed: bool,
+}
+
+impl InstanceDiff {
+    /// The most significant version component that changed, if any.
+    pub fn get_version_change(&self) -> Option<VersionLevel> {
+        self.version_change
+    }
+    
+    /// The time between the two instances, negative if the second is the earlier one.
+    pub fn get_elapsed(&self) -> SignedDuration {
+        self.elapsed
+    }
+    
+    pub fn is_file_name_changed(&self) -> bool {
+        self.file_name_changed
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FileType {
+    Image,
+    Video,
+    Audio,
+    Binary,
+    Document,
+    CodeFile,
     MarkdownNote,
     Archive,
     Specialized,
     Other
 }
 
+impl FileType {
+    /// Guesses the file type from a file extension, ignoring case and a leading `.`. Unknown
+    /// extensions map to `FileType::Other`.
+    pub fn from_extension(extension: &str) -> FileType {
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        
+        match extension.to_lowercase().as_str() {
+            "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "svg" => FileType::Image,
+            "mp4" | "mov" | "mkv" | "webm" | "avi" => FileType::Video,
+            "mp3" | "wav" | "flac" | "ogg" | "m4a" => FileType::Audio,
+            "md" | "markdown" => FileType::MarkdownNote,
+            "rs" | "py" | "js" | "ts" | "c" | "cpp" | "h" | "java" | "go" => FileType::CodeFile,
+            "zip" | "tar" | "gz" | "7z" | "rar" => FileType::Archive,
+            "pdf" | "docx" | "doc" | "odt" | "txt" => FileType::Document,
+            _ => FileType::Other,
+        }
+    }
+    
+    pub fn category(&self) -> FileCategory {
+        match self {
+            FileType::Image | FileType::Video | FileType::Audio => FileCategory::Media,
+            FileType::MarkdownNote | FileType::CodeFile | FileType::Document => FileCategory::Text,
+            FileType::Binary | FileType::Archive => FileCategory::Data,
+            FileType::Specialized | FileType::Other => FileCategory::Other,
+        }
+    }
+    
+    /// Whether a UI can usually render a preview of the file, i.e. media and text files.
+    pub fn is_previewable(&self) -> bool {
+        matches!(self.category(), FileCategory::Media | FileCategory::Text)
+    }
+    
+    /// A default MIME type for the file type. Most types can't be narrowed down without the
+    /// extension and fall back to `application/octet-stream`; see `Item::mime_type`.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            FileType::MarkdownNote => "text/markdown",
+            FileType::CodeFile => "text/plain",
+            _ => "application/octet-stream",
+        }
+    }
+}
+
+/// Broad groupings of file types, e.g. for deciding how to render a preview.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum FileCategory {
+    Media,
+    Text,
+    Data,
+    Other,
+}
+
+fn mime_type_for_extension(extension: &str) -> Option<&'static str> {
+    let mime_type = match extension.to_lowercase().as_str() {
+        "jpg" | "jpeg" => "image/jpeg",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "svg" => "image/svg+xml",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        "avi" => "video/x-msvideo",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "flac" => "audio/flac",
+        "ogg" => "audio/ogg",
+        "m4a" => "audio/mp4",
+        "md" | "markdown" => "text/markdown",
+        "txt" => "text/plain",
+        "js" => "text/javascript",
+        "pdf" => "application/pdf",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "zip" => "application/zip",
+        "tar" => "application/x-tar",
+        "gz" => "application/gzip",
+        "7z" => "application/x-7z-compressed",
+        "rar" => "application/vnd.rar",
+        _ => return None,
+    };
+    
+    Some(mime_type)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::version::Version;
     use super::*;
     
     #[test]
@@ -199,7 +960,7 @@ mod tests {
         
         let tag = Tag::new(String::from("Test Tag"));
         let tag_id = tag.get_id().to_string();
-        item.add_tag(tag);
+        item.add_tag(tag)?;
         assert_eq!(item.tags.len(), 1);
         
         item.remove_tag(&tag_id).unwrap();
@@ -207,4 +968,817 @@ mod tests {
         
         Ok(())
     }
+    
+    #[test]
+    fn test_item_move_to_folder() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let file_name = item.instances.latest().unwrap().file_name.to_string();
+        
+        item.move_to_folder(String::from("res/archive"))?;
+        
+        assert_eq!(item.get_folder(), "res/archive");
+        assert_eq!(item.current_file_path()?, format!("res/archive/{}.jpeg", file_name));
+        
+        let latest = item.instances.latest().unwrap().get_instance();
+        assert!(latest.is_type_of(InstanceType::Update));
+        assert_eq!(latest.get_change_note(), "Moved from res/files to res/archive");
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_move_to_folder_rejects_invalid_folder() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        
+        assert!(matches!(item.move_to_folder(String::from("res/archive/")), Err(ItemError::FilePath(_))));
+        assert!(matches!(item.move_to_folder(String::new()), Err(ItemError::FilePath(_))));
+        assert_eq!(item.get_folder(), "res/files");
+        assert_eq!(item.instances.len(), 1);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_change_extension() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("png"), FileType::Image)?;
+        
+        item.change_extension(String::from("webp"), String::from("Re-encoded as webp"))?;
+        
+        assert_eq!(item.get_extension(), "webp");
+        assert!(item.current_file_path()?.ends_with(".webp"));
+        assert_eq!(item.instances.latest().unwrap().get_instance().get_change_note(), "Re-encoded as webp");
+        assert_eq!(item.instances.latest().unwrap().file_name.get_version(), &Version::new(0, 1, 1));
+        assert!(item.instances.latest().unwrap().get_instance().is_type_of(InstanceType::Rename));
+        assert!(!item.is_deleted());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_change_extension_rejects_invalid_extension() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("png"), FileType::Image)?;
+        
+        for extension in ["tar.gz", "a/b", "a\\b", "we bp", "", "."] {
+            let result = item.change_extension(String::from(extension), String::from("Test Change"));
+            assert!(matches!(result, Err(ItemError::FilePath(_))));
+        }
+        
+        assert_eq!(item.get_extension(), "png");
+        assert_eq!(item.instances.len(), 1);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_change_extension_normalizes_extension() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("png"), FileType::Image)?;
+        
+        item.change_extension(String::from(".WebP"), String::from("Re-encoded as webp"))?;
+        
+        assert_eq!(item.get_extension(), "webp");
+        assert!(item.current_file_path()?.ends_with(".webp"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_edit_title() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        
+        item.edit_title(String::from("Holiday"), None)?;
+        
+        assert_eq!(item.get_title(), Some("Holiday"));
+        assert_eq!(item.current_version()?, &Version::new(0, 1, 1));
+        
+        let latest = item.instances.latest().unwrap().get_instance();
+        assert!(latest.is_type_of(InstanceType::Rename));
+        assert!(latest.get_change_note().contains("Holiday"));
+        
+        item.edit_title(String::from("Beach"), Some(String::from("Better title")))?;
+        assert_eq!(item.get_title(), Some("Beach"));
+        assert_eq!(item.instances.latest().unwrap().get_instance().get_change_note(), "Better title");
+        assert!(!item.is_deleted());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_preview_next_version() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        
+        for level in [VersionLevel::Patch, VersionLevel::Minor, VersionLevel::Major] {
+            let instances_before = item.total_instances();
+            let preview = item.preview_next_version(level)?;
+            assert_eq!(item.total_instances(), instances_before);
+            
+            item.edit(String::from("Test Change"), level)?;
+            
+            assert_eq!(item.total_instances(), instances_before + 1);
+            assert_eq!(item.current_version()?, &preview);
+        }
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_touch() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        item.edit_with_hash(String::from("Test Change"), VersionLevel::Minor, Some(String::from("abc123")))?;
+        let version = *item.current_version()?;
+        let path = item.current_file_path()?;
+        
+        item.touch(String::from("Tagged"))?;
+        item.touch(String::from(""))?;
+        
+        assert_eq!(item.total_instances(), 4);
+        assert_eq!(item.current_version()?, &version);
+        assert_eq!(item.current_file_path()?, path);
+        assert_eq!(item.current_hash(), Some("abc123"));
+        assert_eq!(item.instances.latest().unwrap().get_instance().get_change_note(), "Instance Touched");
+        assert!(item.instances.latest().unwrap().get_instance().is_type_of(InstanceType::Touch));
+        assert_eq!(item.instances.get_by_version(&version).unwrap().get_instance().get_change_note(), "Test Change");
+        assert_eq!(item.all_file_paths()?.len(), 2);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_was_ever_deleted() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        item.edit(String::from("Test Change"), VersionLevel::Patch)?;
+        assert!(!item.was_ever_deleted());
+        
+        item.delete(None)?;
+        assert!(item.was_ever_deleted());
+        
+        item.restore(None)?;
+        assert!(!item.is_deleted());
+        assert!(item.was_ever_deleted());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_rename_title() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let path_before = item.current_file_path()?;
+        
+        let (old_path, new_path) = item.rename_title(String::from("Holiday"), None)?;
+        
+        assert_eq!(old_path, path_before);
+        assert_eq!(new_path, item.current_file_path()?);
+        assert_eq!(old_path, new_path);
+        assert_eq!(item.get_title(), Some("Holiday"));
+        assert!(item.instances.latest().unwrap().get_instance().is_type_of(InstanceType::Rename));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_add_duplicate_tag() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let cats = Tag::new(String::from("Cats"));
+        
+        item.add_tag(cats.clone())?;
+        assert!(matches!(item.add_tag(cats), Err(ItemError::DuplicateTag(_))));
+        
+        item.add_tag(Tag::new(String::from("cats")))?;
+        item.add_tag(Tag::new(String::from("Dogs")))?;
+        assert_eq!(item.get_tags().len(), 3);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_tag_values() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let mut birds = Tag::new(String::from("Birds"));
+        birds.delete(None)?;
+        item.add_tags(vec![Tag::new(String::from("Cats")), Tag::new(String::from("Dogs")), birds])?;
+        
+        assert_eq!(item.tag_values(false)?, vec!["Cats", "Dogs"]);
+        assert_eq!(item.tag_values(true)?, vec!["Cats", "Dogs", "Birds"]);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_add_unique_tag() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        
+        item.add_unique_tag(Tag::new(String::from("Cats")))?;
+        let result = item.add_unique_tag(Tag::new(String::from("cats")));
+        assert!(matches!(result, Err(ItemError::DuplicateTagValue(ref value)) if value == "cats"));
+        
+        item.add_unique_tag(Tag::new(String::from("Dogs")))?;
+        assert_eq!(item.tag_values(false)?, vec![String::from("Cats"), String::from("Dogs")]);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_add_unique_tag_ignores_deleted_tags() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let mut cats = Tag::new(String::from("Cats"));
+        cats.delete(None)?;
+        item.add_tag(cats.clone())?;
+        
+        item.add_unique_tag(Tag::new(String::from("cats")))?;
+        assert_eq!(item.tag_values(true)?, vec![String::from("Cats"), String::from("cats")]);
+        assert!(matches!(item.add_unique_tag(cats), Err(ItemError::DuplicateTag(_))));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_add_tags() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        item.add_tag(Tag::new(String::from("Cats")))?;
+        
+        item.add_tags(vec![Tag::new(String::from("Dogs")), Tag::new(String::from("Birds"))])?;
+        assert_eq!(item.get_tags().len(), 3);
+        
+        let cats = item.find_tag_by_value("Cats").unwrap().clone();
+        let result = item.add_tags(vec![Tag::new(String::from("Fish")), cats]);
+        assert!(matches!(result, Err(ItemError::DuplicateTag(_))));
+        
+        let fish = Tag::new(String::from("Fish"));
+        let result = item.add_tags(vec![fish.clone(), fish]);
+        assert!(matches!(result, Err(ItemError::DuplicateTag(_))));
+        
+        assert_eq!(item.get_tags().len(), 3);
+        assert!(item.find_tag_by_value("Fish").is_none());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_remove_tags() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let cats = Tag::new(String::from("Cats"));
+        let dogs = Tag::new(String::from("Dogs"));
+        let cats_id = cats.get_id().to_string();
+        let dogs_id = dogs.get_id().to_string();
+        item.add_tags(vec![cats, dogs, Tag::new(String::from("Birds"))])?;
+        
+        assert_eq!(item.remove_tags(&[&cats_id, &dogs_id, "missing"])?, 2);
+        assert_eq!(item.get_tags().len(), 1);
+        assert_eq!(item.remove_tags(&[&cats_id])?, 0);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_tag_accessors() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let cats = Tag::new(String::from("Cats"));
+        let cats_id = cats.get_id().to_string();
+        
+        item.add_tag(cats)?;
+        item.add_tag(Tag::new(String::from("Dogs")))?;
+        
+        let values: Vec<String> = item.get_tags().iter().map(|tag| tag.get_value().unwrap()).collect();
+        assert_eq!(values, vec!["Cats", "Dogs"]);
+        
+        assert!(item.has_tag(&cats_id));
+        assert!(!item.has_tag("missing"));
+        
+        assert_eq!(item.find_tag_by_value("Cats").unwrap().get_id(), cats_id);
+        assert!(item.find_tag_by_value("Birds").is_none());
+        
+        assert_eq!(item.find_tag_by_value_ci("CATS").unwrap().get_id(), cats_id);
+        assert!(item.find_tag_by_value("cats").is_none());
+        assert!(item.find_tag_by_value_ci("Birds").is_none());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_file_type_category() {
+        let categories = [
+            (FileType::Image, FileCategory::Media),
+            (FileType::Video, FileCategory::Media),
+            (FileType::Audio, FileCategory::Media),
+            (FileType::MarkdownNote, FileCategory::Text),
+            (FileType::CodeFile, FileCategory::Text),
+            (FileType::Document, FileCategory::Text),
+            (FileType::Binary, FileCategory::Data),
+            (FileType::Archive, FileCategory::Data),
+            (FileType::Specialized, FileCategory::Other),
+            (FileType::Other, FileCategory::Other),
+        ];
+        
+        for (file_type, category) in categories {
+            assert_eq!(file_type.category(), category);
+            assert_eq!(file_type.is_previewable(), matches!(category, FileCategory::Media | FileCategory::Text));
+        }
+    }
+    
+    #[test]
+    fn test_file_type_from_extension() {
+        assert_eq!(FileType::from_extension("jpg"), FileType::Image);
+        assert_eq!(FileType::from_extension("PNG"), FileType::Image);
+        assert_eq!(FileType::from_extension("mov"), FileType::Video);
+        assert_eq!(FileType::from_extension("wav"), FileType::Audio);
+        assert_eq!(FileType::from_extension("md"), FileType::MarkdownNote);
+        assert_eq!(FileType::from_extension("Rs"), FileType::CodeFile);
+        assert_eq!(FileType::from_extension("tar"), FileType::Archive);
+        assert_eq!(FileType::from_extension("pdf"), FileType::Document);
+        assert_eq!(FileType::from_extension("xyz"), FileType::Other);
+        assert_eq!(FileType::from_extension(""), FileType::Other);
+    }
+    
+    #[test]
+    fn test_item_new_auto() -> Result<(), ItemError> {
+        let item = Item::new_auto(String::from("res/files"), String::from("MP4"))?;
+        assert_eq!(item.get_file_type(), FileType::Video);
+        
+        let item = Item::new_auto(String::from("res/files"), String::from("unknown"))?;
+        assert_eq!(item.get_file_type(), FileType::Other);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_new_validates_extension() -> Result<(), ItemError> {
+        let item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        assert_eq!(item.get_extension(), "jpeg");
+        
+        let item = Item::new(String::from("res/files"), String::from(".jpeg"), FileType::Image)?;
+        assert_eq!(item.get_extension(), "jpeg");
+        assert!(item.current_file_path()?.ends_with(".jpeg"));
+        assert!(!item.current_file_path()?.ends_with("..jpeg"));
+        
+        for extension in ["a/b", "a\\b", "jp eg", "", "."] {
+            let result = Item::new(String::from("res/files"), String::from(extension), FileType::Image);
+            assert!(matches!(result, Err(ItemError::FilePath(_))), "{:?} should be rejected", extension);
+        }
+        
+        let item = Item::new_auto(String::from("res/files"), String::from(".png"))?;
+        assert_eq!(item.get_file_type(), FileType::Image);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_parse_file_path() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        item.edit(String::from("Cropped"), VersionLevel::Minor)?;
+        
+        let (folder, file_name, extension) = parse_file_path(&item.current_file_path()?)?;
+        assert_eq!(folder, "res/files");
+        assert_eq!(file_name.get_version(), item.current_version()?);
+        assert_eq!(extension, "jpeg");
+        
+        let (_, file_name, extension) = parse_file_path("res/files/2024-07-30-00-56-25-031870928-0600_1-2-3-beta.1.tar")?;
+        assert_eq!(file_name.get_version().get_pre_release(), Some("beta.1"));
+        assert_eq!(extension, "tar");
+        
+        for path in ["2024-07-30-00-56-25-031870928-0600_1-2-3.jpeg", "res/files/2024-07-30-00-56-25-031870928-0600_1-2-3", "/2024-07-30-00-56-25-031870928-0600_1-2-3.jpeg"] {
+            assert!(matches!(parse_file_path(path), Err(ItemError::FilePath(_))), "{:?} should be rejected", path);
+        }
+        
+        assert!(matches!(parse_file_path("res/files/not-a-file-name.jpeg"), Err(ItemError::FileName(_))));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_builder() -> Result<(), ItemError> {
+        let beach = Tag::new(String::from("beach"));
+        let beach_id = beach.get_id().to_string();
+        
+        let item = Item::builder()
+            .folder("res/files")
+            .extension("png")
+            .title("Holiday")
+            .tag(beach)
+            .tag(Tag::new(String::from("summer")))
+            .build()?;
+        
+        assert_eq!(item.get_folder(), "res/files");
+        assert_eq!(item.get_file_type(), FileType::Image);
+        assert_eq!(item.get_title(), Some("Holiday"));
+        assert_eq!(item.get_tags().len(), 2);
+        assert!(item.has_tag(&beach_id));
+        assert!(item.find_tag_by_value("summer").is_some());
+        assert_eq!(item.instances.len(), 1);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_builder_validation() {
+        assert!(matches!(Item::builder().extension("png").build(), Err(ItemError::FilePath(_))));
+        assert!(matches!(Item::builder().folder("res/files").build(), Err(ItemError::FilePath(_))));
+        assert!(matches!(Item::builder().folder("res/files/").extension("png").build(), Err(ItemError::FilePath(_))));
+        
+        let item = Item::builder().folder("res/files").extension("png").file_type(FileType::Specialized).build().unwrap();
+        assert_eq!(item.get_file_type(), FileType::Specialized);
+    }
+    
+    #[test]
+    fn test_current_version() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        assert_eq!(item.current_version()?, &Version::new(0, 1, 0));
+        assert!(!item.is_deleted());
+        
+        item.edit(String::from("Cropped"), VersionLevel::Patch)?;
+        assert_eq!(item.current_version()?, &Version::new(0, 1, 1));
+        
+        item.delete(None)?;
+        assert!(item.is_deleted());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_duplicate() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        item.edit(String::from("Cropped"), VersionLevel::Minor)?;
+        item.add_tag(Tag::new(String::from("Cats")))?;
+        
+        let copy = item.duplicate();
+        
+        assert_ne!(copy.get_id(), item.get_id());
+        assert_eq!(copy.current_version()?, item.current_version()?);
+        assert_eq!(copy.instances.len(), item.instances.len());
+        assert_eq!(copy.current_file_path()?, item.current_file_path()?);
+        assert!(copy.find_tag_by_value("Cats").is_some());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_identity_equality() -> Result<(), ItemError> {
+        let item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let other = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        assert!(item != other);
+        
+        let mut clone = item.clone();
+        clone.edit(String::from("Cropped"), VersionLevel::Patch)?;
+        assert!(item == clone);
+        assert!(item != item.duplicate());
+        
+        let items: std::collections::HashSet<Item> = [item.clone(), clone, other, item.duplicate()].into_iter().collect();
+        assert_eq!(items.len(), 3);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_instance_content_eq() {
+        let instance = ItemInstance::new();
+        let mut copy = instance.clone();
+        copy.id = UuidGenerator.generate();
+        
+        assert_ne!(instance.get_id(), copy.get_id());
+        assert!(instance.content_eq(&copy));
+        assert!(instance.get_instance().content_eq(copy.get_instance()));
+        
+        copy.content_hash = Some(String::from("abc123"));
+        assert!(!instance.content_eq(&copy));
+        
+        assert!(!instance.content_eq(&ItemInstance::new()));
+    }
+    
+    #[test]
+    fn test_item_instance_diff() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        item.edit(String::from("Cropped"), VersionLevel::Minor)?;
+        
+        let initial = item.instances.earliest().unwrap();
+        let edited = item.instances.latest().unwrap();
+        
+        let diff = initial.diff(edited);
+        assert_eq!(diff.get_version_change(), Some(VersionLevel::Minor));
+        assert!(diff.get_elapsed() > SignedDuration::ZERO);
+        assert!(diff.is_file_name_changed());
+        
+        let diff = edited.diff(initial);
+        assert!(diff.get_elapsed() < SignedDuration::ZERO);
+        
+        let diff = initial.diff(initial);
+        assert!(diff.get_version_change().is_none());
+        assert!(!diff.is_file_name_changed());
+        assert_eq!(diff.clone(), initial.diff(initial));
+        assert_ne!(diff, initial.diff(edited));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_edit_count() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        assert_eq!(item.edit_count(), 0);
+        assert_eq!(item.total_instances(), 1);
+        
+        item.edit(String::from("Cropped"), VersionLevel::Patch)?;
+        item.edit(String::from("Recolored"), VersionLevel::Minor)?;
+        item.delete(None)?;
+        item.restore(None)?;
+        
+        assert_eq!(item.edit_count(), 2);
+        assert_eq!(item.total_instances(), 5);
+        
+        item.touch(String::from("Tagged"))?;
+        assert_eq!(item.edit_count(), 2);
+        assert_eq!(item.total_instances(), 6);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_created_and_modified_at() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let created_at = item.created_at()?.clone();
+        assert_eq!(item.modified_at()?, &created_at);
+        
+        item.edit(String::from("Cropped"), VersionLevel::Patch)?;
+        
+        assert_eq!(item.created_at()?, &created_at);
+        assert!(*item.modified_at()? > created_at);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_all_file_paths() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        let initial_path = item.current_file_path()?;
+        
+        item.edit(String::from("Cropped"), VersionLevel::Patch)?;
+        let cropped_path = item.current_file_path()?;
+        
+        item.edit(String::from("Recolored"), VersionLevel::Patch)?;
+        let recolored_path = item.current_file_path()?;
+        
+        item.delete(None)?;
+        assert_eq!(item.current_file_path()?, recolored_path);
+        
+        assert_eq!(item.all_file_paths()?, vec![initial_path, cropped_path, recolored_path]);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_all_file_paths_after_move_and_extension_change() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("png"), FileType::Image)?;
+        let initial_path = item.current_file_path()?;
+        
+        item.move_to_folder(String::from("res/archive"))?;
+        let moved_path = item.current_file_path()?;
+        assert!(moved_path.starts_with("res/archive/"));
+        
+        item.change_extension(String::from("webp"), String::from("Re-encoded as webp"))?;
+        let webp_path = item.current_file_path()?;
+        assert!(webp_path.ends_with(".webp"));
+        
+        let paths = item.all_file_paths()?;
+        assert_eq!(paths, vec![initial_path.clone(), moved_path, webp_path]);
+        assert!(paths[0].starts_with("res/files/") && paths[0].ends_with(".png"));
+        assert_eq!(item.instances.earliest().unwrap().get_folder(), Some("res/files"));
+        assert_eq!(item.instances.earliest().unwrap().get_extension(), Some("png"));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_latest_file_name() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        assert_eq!(item.latest_file_name()?.get_version(), item.current_version()?);
+        
+        item.edit(String::from("Cropped"), VersionLevel::Minor)?;
+        assert_eq!(item.latest_file_name()?.get_version(), item.current_version()?);
+        assert!(item.current_file_path()?.contains(&item.latest_file_name()?.to_string()));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_set_version() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        
+        item.set_version(Version::new(3, 2, 0), String::from("Imported"))?;
+        assert_eq!(item.current_version()?, &Version::new(3, 2, 0));
+        assert_eq!(item.instances.latest().unwrap().file_name.get_version(), &Version::new(3, 2, 0));
+        
+        item.edit(String::from("Cropped"), VersionLevel::Patch)?;
+        assert_eq!(item.current_version()?, &Version::new(3, 2, 1));
+        
+        for target in [Version::new(3, 2, 1), Version::new(1, 0, 0)] {
+            let result = item.set_version(target, String::from("Imported"));
+            assert!(matches!(result, Err(ItemError::Instance(InstanceError::VersionRegression(_, _)))));
+        }
+        
+        assert_eq!(item.current_version()?, &Version::new(3, 2, 1));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_restore_to() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        item.edit_with_hash(String::from("Cropped"), VersionLevel::Patch, Some(String::from("abc123")))?;
+        let cropped_file_name = item.latest_file_name()?.clone();
+        item.edit(String::from("Recolored"), VersionLevel::Patch)?;
+        
+        let result = item.restore_to(&Version::new(0, 1, 1), None);
+        assert!(matches!(result, Err(ItemError::Instance(InstanceError::CannotRestoreNonDeleted))));
+        
+        item.delete(None)?;
+        item.restore_to(&Version::new(0, 1, 1), None)?;
+        
+        assert!(!item.is_deleted());
+        assert_eq!(item.current_version()?, &Version::new(2, 0, 0));
+        assert_eq!(item.latest_file_name()?, &cropped_file_name);
+        assert_eq!(item.current_hash(), Some("abc123"));
+        assert_eq!(item.instances.latest().unwrap().get_instance().get_change_note(), "Restored to version 0.1.1");
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_restore_to_across_extension_change() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("png"), FileType::Image)?;
+        let png_path = item.current_file_path()?;
+        
+        item.change_extension(String::from("webp"), String::from("Re-encoded as webp"))?;
+        item.move_to_folder(String::from("res/archive"))?;
+        item.delete(None)?;
+        item.restore_to(&Version::new(0, 1, 0), None)?;
+        
+        assert_eq!(item.get_extension(), "png");
+        assert_eq!(item.get_folder(), "res/files");
+        assert_eq!(item.current_file_path()?, png_path);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_restore_to_missing_version() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        item.delete(None)?;
+        
+        let result = item.restore_to(&Version::new(0, 5, 0), None);
+        assert!(matches!(result, Err(ItemError::VersionNotFound(_))));
+        assert!(item.is_deleted());
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_edit_with_size() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        assert_eq!(item.current_size(), None);
+        assert_eq!(item.total_size_across_versions(), 0);
+        
+        item.edit_with_size(String::from("Upload"), VersionLevel::Minor, 1_024)?;
+        item.edit_with_size(String::from("Re-upload"), VersionLevel::Minor, 2_048)?;
+        assert_eq!(item.current_size(), Some(2_048));
+        assert_eq!(item.total_size_across_versions(), 3_072);
+        
+        item.touch(String::from("Tagged"))?;
+        assert_eq!(item.current_size(), Some(2_048));
+        assert_eq!(item.total_size_across_versions(), 3_072);
+        
+        item.edit(String::from("Unsized edit"), VersionLevel::Patch)?;
+        assert_eq!(item.current_size(), None);
+        assert_eq!(item.total_size_across_versions(), 3_072);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_edit_with_hash() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        assert_eq!(item.current_hash(), None);
+        
+        item.edit_with_hash(String::from("Re-upload"), VersionLevel::Minor, Some(String::from("9f86d081884C7D65")))?;
+        assert_eq!(item.current_hash(), Some("9f86d081884C7D65"));
+        
+        let latest = item.instances.latest().unwrap();
+        assert_eq!(latest.get_content_hash(), Some("9f86d081884C7D65"));
+        assert_eq!(latest.get_instance().get_change_note(), "Re-upload");
+        
+        item.edit(String::from("Unhashed edit"), VersionLevel::Patch)?;
+        assert_eq!(item.current_hash(), None);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_hash_and_size_survive_non_content_changes() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("png"), FileType::Image)?;
+        item.add_edit_instance(String::from("Upload"), VersionLevel::Minor, Some(String::from("9f86d081")), Some(1_024))?;
+        
+        item.edit_title(String::from("Holiday"), None)?;
+        assert_eq!(item.current_hash(), Some("9f86d081"));
+        assert_eq!(item.current_size(), Some(1_024));
+        
+        item.move_to_folder(String::from("res/archive"))?;
+        assert_eq!(item.current_hash(), Some("9f86d081"));
+        assert_eq!(item.current_size(), Some(1_024));
+        
+        item.delete(None)?;
+        assert_eq!(item.current_hash(), Some("9f86d081"));
+        assert_eq!(item.current_size(), Some(1_024));
+        
+        item.restore(None)?;
+        assert_eq!(item.current_hash(), Some("9f86d081"));
+        assert_eq!(item.current_size(), Some(1_024));
+        assert_eq!(item.total_size_across_versions(), 1_024);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_change_extension_clears_hash_and_size() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("png"), FileType::Image)?;
+        item.add_edit_instance(String::from("Upload"), VersionLevel::Minor, Some(String::from("9f86d081")), Some(1_024))?;
+        
+        item.change_extension(String::from("webp"), String::from("Re-encoded as webp"))?;
+        assert_eq!(item.current_hash(), None);
+        assert_eq!(item.current_size(), None);
+        assert_eq!(item.total_size_across_versions(), 1_024);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_edit_with_invalid_hash() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image)?;
+        
+        for hash in ["", "not-hex"] {
+            let result = item.edit_with_hash(String::from("Edit"), VersionLevel::Patch, Some(String::from(hash)));
+            assert!(matches!(result, Err(ItemError::InvalidHash(_))));
+        }
+        
+        assert_eq!(item.instances.len(), 1);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_mime_type() -> Result<(), ItemError> {
+        let item = Item::new_auto(String::from("res/files"), String::from("jpeg"))?;
+        assert_eq!(item.mime_type(), "image/jpeg");
+        
+        let item = Item::new_auto(String::from("res/files"), String::from("MP4"))?;
+        assert_eq!(item.mime_type(), "video/mp4");
+        
+        let item = Item::new_auto(String::from("res/files"), String::from("xyz"))?;
+        assert_eq!(item.mime_type(), "application/octet-stream");
+        
+        let item = Item::new(String::from("res/files"), String::from("note"), FileType::MarkdownNote)?;
+        assert_eq!(item.mime_type(), "text/markdown");
+        
+        Ok(())
+    }
+    
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_item_serde_round_trip() -> Result<(), ItemError> {
+        let mut item = Item::new(String::from("res/files/12154-15152-125"), String::from("jpeg"), FileType::Image)?;
+        item.edit(String::from("Test Change"), VersionLevel::Minor)?;
+        item.edit(String::from("Test Change 2"), VersionLevel::Patch)?;
+        item.delete(None)?;
+        item.add_tag(Tag::new(String::from("Test Tag")))?;
+        
+        let json = serde_json::to_string(&item).unwrap();
+        let loaded: Item = serde_json::from_str(&json).unwrap();
+        
+        assert_eq!(loaded.current_file_path()?, item.current_file_path()?);
+        assert_eq!(loaded.get_id(), item.get_id());
+        assert_eq!(loaded.instances.len(), 4);
+        assert!(loaded.instances.is_deleted());
+        assert_eq!(loaded.tags[0].get_value()?, "Test Tag");
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_item_error_source() {
+        use std::error::Error;
+        
+        let error = ItemError::from(InstanceError::DatetimeIncorrectlyOrdered);
+        let source = error.source().expect("instance error should be the source");
+        
+        assert!(matches!(source.downcast_ref::<InstanceError>(), Some(InstanceError::DatetimeIncorrectlyOrdered)));
+        assert_eq!(error.to_string(), "Item instance error: New instance datetime is before the latest instance datetime");
+    }
+    
+    #[test]
+    fn test_item_new_with_sequential_ids() -> Result<(), ItemError> {
+        let generator = crate::id::SequentialGenerator::new();
+        
+        let item = Item::new_with(String::from("res/files"), String::from("jpeg"), FileType::Image, &generator)?;
+        assert_eq!(item.get_id(), "1");
+        assert_eq!(item.instances.earliest().unwrap().get_id(), "2");
+        
+        let other = Item::new_with(String::from("res/files"), String::from("jpeg"), FileType::Image, &generator)?;
+        assert_eq!(other.get_id(), "3");
+        
+        Ok(())
+    }
 }
\ No newline at end of file
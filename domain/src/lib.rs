@@ -1,5 +1,12 @@
-mod item;
-mod tag;
-mod instance;
-mod version;
-mod file_name;
\ No newline at end of file
+pub mod item;
+pub mod tag;
+pub mod instance;
+pub mod version;
+pub mod file_name;
+pub mod repository;
+pub mod collection;
+pub mod error;
+pub mod id;
+pub mod prelude;
+
+pub use error::TerferError;
\ No newline at end of file
@@ -0,0 +1,19 @@
+//! Re-exports the commonly used types.
+//!
+//! ```
+//! use domain::prelude::*;
+//!
+//! let mut item = Item::new(String::from("res/files"), String::from("png"), FileType::Image).unwrap();
+//! item.add_tag(Tag::new(String::from("beach"))).unwrap();
+//! item.edit(String::from("Cropped"), VersionLevel::Minor).unwrap();
+//!
+//! assert_eq!(item.current_version().unwrap(), &Version::new(0, 2, 0));
+//! assert_eq!(InstanceType::Update.to_string(), "Updated");
+//! ```
+
+pub use crate::error::TerferError;
+pub use crate::file_name::FileName;
+pub use crate::instance::{Instance, InstanceList, InstanceType, Instanced};
+pub use crate::item::{FileType, Item};
+pub use crate::tag::Tag;
+pub use crate::version::{Version, VersionLevel};
\ No newline at end of file
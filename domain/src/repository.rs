@@ -0,0 +1,230 @@
+use std::collections::HashMap;
+use crate::item::Item;
+
+/// Storage for items, keyed by item id.
+pub trait Repository {
+    /// Saves the item, overwriting any stored item with the same id.
+    fn save(&mut self, item: &Item) -> Result<(), RepoError>;
+    
+    fn load(&self, id: &str) -> Result<Option<Item>, RepoError>;
+    
+    /// Removes the item, returning `RepoError::NotFound` if there is no item with the id.
+    fn delete(&mut self, id: &str) -> Result<(), RepoError>;
+    
+    fn list_ids(&self) -> Result<Vec<String>, RepoError>;
+}
+
+/// An in-memory repository, mainly useful for tests.
+#[derive(Default)]
+pub struct HashMapRepository {
+    items: HashMap<String, Item>,
+}
+
+impl HashMapRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Repository for HashMapRepository {
+    fn save(&mut self, item: &Item) -> Result<(), RepoError> {
+        self.items.insert(item.get_id().to_string(), item.clone());
+        Ok(())
+    }
+    
+    fn load(&self, id: &str) -> Result<Option<Item>, RepoError> {
+        Ok(self.items.get(id).cloned())
+    }
+    
+    fn delete(&mut self, id: &str) -> Result<(), RepoError> {
+        match self.items.remove(id) {
+            Some(_) => Ok(()),
+            None => Err(RepoError::NotFound(id.to_string())),
+        }
+    }
+    
+    fn list_ids(&self) -> Result<Vec<String>, RepoError> {
+        let mut ids: Vec<String> = self.items.keys().cloned().collect();
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+/// A repository storing each item as a JSON file named after its id.
+#[cfg(feature = "json")]
+pub struct JsonFileRepository {
+    directory: std::path::PathBuf,
+}
+
+#[cfg(feature = "json")]
+impl JsonFileRepository {
+    /// Creates a repository in `directory`, creating the directory if it doesn't exist.
+    pub fn new(directory: impl Into<std::path::PathBuf>) -> Result<Self, RepoError> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+        
+        Ok(Self {
+            directory,
+        })
+    }
+    
+    /// Rejects ids that are empty or could reach outside the repository directory.
+    fn item_path(&self, id: &str) -> Result<std::path::PathBuf, RepoError> {
+        if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+            return Err(RepoError::InvalidId(id.to_string()));
+        }
+        
+        Ok(self.directory.join(format!("{}.json", id)))
+    }
+}
+
+#[cfg(feature = "json")]
+impl Repository for JsonFileRepository {
+    fn save(&mut self, item: &Item) -> Result<(), RepoError> {
+        let json = serde_json::to_string_pretty(item).map_err(|e| RepoError::Serialize(e.to_string()))?;
+        std::fs::create_dir_all(&self.directory)?;
+        std::fs::write(self.item_path(item.get_id())?, json)?;
+        Ok(())
+    }
+    
+    fn load(&self, id: &str) -> Result<Option<Item>, RepoError> {
+        let path = self.item_path(id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        
+        let json = std::fs::read_to_string(path)?;
+        let item = serde_json::from_str(&json).map_err(|e| RepoError::Deserialize(e.to_string()))?;
+        Ok(Some(item))
+    }
+    
+    fn delete(&mut self, id: &str) -> Result<(), RepoError> {
+        let path = self.item_path(id)?;
+        if !path.exists() {
+            return Err(RepoError::NotFound(id.to_string()));
+        }
+        
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+    
+    fn list_ids(&self) -> Result<Vec<String>, RepoError> {
+        let mut ids = Vec::new();
+        for entry in std::fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == "json") {
+                if let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    ids.push(id.to_string());
+                }
+            }
+        }
+        
+        ids.sort();
+        Ok(ids)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RepoError {
+    #[error("No item found with id: {0}")]
+    NotFound(String),
+    #[error("Invalid item id: {0}")]
+    InvalidId(String),
+    #[error("Repository IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to serialize item: {0}")]
+    Serialize(String),
+    #[error("Failed to deserialize item: {0}")]
+    Deserialize(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::FileType;
+    use crate::version::VersionLevel;
+    
+    #[test]
+    fn test_hash_map_repository() -> Result<(), RepoError> {
+        let mut repository = HashMapRepository::new();
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image).unwrap();
+        let other = Item::new(String::from("res/files"), String::from("png"), FileType::Image).unwrap();
+        
+        repository.save(&item)?;
+        repository.save(&other)?;
+        
+        let mut expected_ids = vec![item.get_id().to_string(), other.get_id().to_string()];
+        expected_ids.sort();
+        assert_eq!(repository.list_ids()?, expected_ids);
+        
+        let loaded = repository.load(item.get_id())?.unwrap();
+        assert_eq!(loaded.get_id(), item.get_id());
+        assert_eq!(loaded.get_extension(), "jpeg");
+        
+        item.edit(String::from("Cropped"), VersionLevel::Patch).unwrap();
+        repository.save(&item)?;
+        assert_eq!(repository.list_ids()?.len(), 2);
+        assert_eq!(repository.load(item.get_id())?.unwrap().current_version().unwrap(), item.current_version().unwrap());
+        
+        repository.delete(item.get_id())?;
+        assert!(repository.load(item.get_id())?.is_none());
+        assert_eq!(repository.list_ids()?, vec![other.get_id().to_string()]);
+        assert!(matches!(repository.delete(item.get_id()), Err(RepoError::NotFound(_))));
+        
+        Ok(())
+    }
+    
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_file_repository() -> Result<(), RepoError> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut repository = JsonFileRepository::new(temp_dir.path().join("items"))?;
+        let mut item = Item::new(String::from("res/files"), String::from("jpeg"), FileType::Image).unwrap();
+        item.edit(String::from("Cropped"), VersionLevel::Patch).unwrap();
+        
+        repository.save(&item)?;
+        assert_eq!(repository.list_ids()?, vec![item.get_id().to_string()]);
+        
+        let loaded = repository.load(item.get_id())?.unwrap();
+        assert_eq!(loaded.get_id(), item.get_id());
+        assert_eq!(loaded.current_version().unwrap(), item.current_version().unwrap());
+        assert_eq!(loaded.current_file_path().unwrap(), item.current_file_path().unwrap());
+        
+        assert!(repository.load("missing")?.is_none());
+        
+        repository.delete(item.get_id())?;
+        assert!(repository.list_ids()?.is_empty());
+        assert!(matches!(repository.delete(item.get_id()), Err(RepoError::NotFound(_))));
+        
+        Ok(())
+    }
+    
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_file_repository_corrupt_file() -> Result<(), RepoError> {
+        let temp_dir = tempfile::tempdir()?;
+        let repository = JsonFileRepository::new(temp_dir.path())?;
+        std::fs::write(temp_dir.path().join("corrupt.json"), "{ not json")?;
+        
+        assert!(matches!(repository.load("corrupt"), Err(RepoError::Deserialize(_))));
+        
+        Ok(())
+    }
+    
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_file_repository_rejects_invalid_ids() -> Result<(), RepoError> {
+        let temp_dir = tempfile::tempdir()?;
+        let mut repository = JsonFileRepository::new(temp_dir.path().join("items"))?;
+        std::fs::write(temp_dir.path().join("outside.json"), "{}")?;
+        
+        for id in ["../outside", "a/b", "a\\b", "..", ""] {
+            assert!(matches!(repository.load(id), Err(RepoError::InvalidId(_))));
+            assert!(matches!(repository.delete(id), Err(RepoError::InvalidId(_))));
+        }
+        
+        assert!(temp_dir.path().join("outside.json").exists());
+        
+        Ok(())
+    }
+}
\ No newline at end of file
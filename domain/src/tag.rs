@@ -1,7 +1,10 @@
-use uuid::Uuid;
-use crate::instance::{Instance, Instanced, InstanceError, InstanceList};
-use crate::version::VersionLevel;
+use jiff::Zoned;
+use crate::id::{IdGenerator, UuidGenerator};
+use crate::instance::{Instance, Instanced, InstancedMut, InstanceError, InstanceList};
+use crate::version::{Version, VersionLevel};
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tag {
     id: String,
     instances: InstanceList<TagInstance>,
@@ -9,9 +12,15 @@ pub struct Tag {
 
 impl Tag {
     pub fn new(value: String) -> Self {
+        Self::new_with(value, &UuidGenerator)
+    }
+    
+    /// Like `new`, but takes the tag's id and the id of its first instance from `generator`.
+    /// Instances recorded later still get random ids.
+    pub fn new_with(value: String, generator: &dyn IdGenerator) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
-            instances: InstanceList::new(Vec::from([TagInstance::new(value)])),
+            id: generator.generate(),
+            instances: InstanceList::new(Vec::from([TagInstance::new_with(value, generator)])),
         }
     }
     
@@ -21,8 +30,82 @@ impl Tag {
             None => return Err(TagError::EditEmptyTag),
         };
         
-        let new_instance = tag_instance.get_instance().create_child_instance(note, VersionLevel::Major);
-        self.instances.add(TagInstance::with_instance(value, new_instance))?;
+        let new_instance = tag_instance.get_instance().create_child_instance(note, VersionLevel::Major)?;
+        let mut new_tag_instance = TagInstance::from_previous(tag_instance, new_instance);
+        new_tag_instance.value = value;
+        self.instances.add(new_tag_instance)?;
+        
+        Ok(())
+    }
+    
+    /// Sets or clears the parent tag, recording the change as a new instance. Cycle
+    /// detection is the caller's responsibility for now.
+    pub fn set_parent(&mut self, parent_id: Option<String>) -> Result<(), TagError> {
+        let tag_instance = match self.instances.latest() {
+            Some(instance) => instance,
+            None => return Err(TagError::EditEmptyTag),
+        };
+        
+        let note = match &parent_id {
+            Some(parent_id) => format!("Parent set to {}", parent_id),
+            None => String::from("Parent cleared"),
+        };
+        
+        let new_instance = tag_instance.get_instance().create_child_instance(note, VersionLevel::Minor)?;
+        let mut new_tag_instance = TagInstance::from_previous(tag_instance, new_instance);
+        new_tag_instance.parent_id = parent_id;
+        self.instances.add(new_tag_instance)?;
+        
+        Ok(())
+    }
+    
+    /// Sets the display color, a `#` followed by six hex digits, recording a new instance.
+    pub fn set_color(&mut self, color: String, note: String) -> Result<(), TagError> {
+        if !is_valid_color(&color) {
+            return Err(TagError::InvalidColor(color));
+        }
+        
+        let tag_instance = match self.instances.latest() {
+            Some(instance) => instance,
+            None => return Err(TagError::EditEmptyTag),
+        };
+        
+        let new_instance = tag_instance.get_instance().create_child_instance(note, VersionLevel::Minor)?;
+        let mut new_tag_instance = TagInstance::from_previous(tag_instance, new_instance);
+        new_tag_instance.color = Some(color);
+        self.instances.add(new_tag_instance)?;
+        
+        Ok(())
+    }
+    
+    /// Merges a duplicate tag's history into this one in chronological order (see
+    /// `InstanceList::merge`), then records a final instance noting the merge that restores
+    /// this tag's current attributes. The tag keeps its own id.
+    pub fn merge_from(&mut self, other: &Tag, note: String) -> Result<(), TagError> {
+        if other.id == self.id {
+            return Err(TagError::MergeWithSelf);
+        }
+        
+        if other.is_deleted() {
+            return Err(TagError::MergeFromDeleted(other.id.clone()));
+        }
+        
+        let current = match self.instances.latest() {
+            Some(instance) => instance.clone(),
+            None => return Err(TagError::EditEmptyTag),
+        };
+        
+        let mut merged = self.instances.clone();
+        merged.merge(&other.instances)?;
+        
+        let merged_latest = match merged.latest() {
+            Some(instance) => instance,
+            None => return Err(TagError::EditEmptyTag),
+        };
+        
+        let new_instance = merged_latest.get_instance().create_child_instance(note, VersionLevel::Major)?;
+        merged.add(TagInstance::from_previous(&current, new_instance))?;
+        self.instances = merged;
         
         Ok(())
     }
@@ -33,8 +116,8 @@ impl Tag {
             None => return Err(TagError::EditEmptyTag),
         };
         
-        let new_instance = tag_instance.get_instance().create_deletion_instance(note);
-        self.instances.add(TagInstance::with_instance(tag_instance.value.clone(), new_instance))?;
+        let new_instance = tag_instance.get_instance().create_deletion_instance(note)?;
+        self.instances.add(TagInstance::from_previous(tag_instance, new_instance))?;
         
         Ok(())
     }
@@ -45,8 +128,8 @@ impl Tag {
             None => return Err(TagError::EditEmptyTag),
         };
         
-        let new_instance = tag_instance.get_instance().create_restoration_instance(note);
-        self.instances.add(TagInstance::with_instance(tag_instance.value.clone(), new_instance))?;
+        let new_instance = tag_instance.get_instance().create_restoration_instance(note)?;
+        self.instances.add(TagInstance::from_previous(tag_instance, new_instance))?;
         
         Ok(())
     }
@@ -61,53 +144,100 @@ impl Tag {
             None => Err(TagError::RetrieveEmptyTag),
         }
     }
+    
+    /// Compares the current value against `query`, ignoring case and surrounding whitespace.
+    pub fn value_matches(&self, query: &str) -> bool {
+        match self.instances.latest() {
+            Some(instance) => instance.value.trim().to_lowercase() == query.trim().to_lowercase(),
+            None => false,
+        }
+    }
+    
+    /// The version, value and datetime of each instance, oldest first.
+    pub fn history(&self) -> impl Iterator<Item = (&Version, &str, &Zoned)> {
+        self.instances.iter().map(|instance| {
+            let meta = instance.get_instance();
+            (meta.get_version(), instance.value.as_str(), meta.get_datetime())
+        })
+    }
+    
+    pub fn is_deleted(&self) -> bool {
+        self.instances.is_deleted()
+    }
+    
+    pub fn current_version(&self) -> Result<Version, TagError> {
+        match self.instances.latest() {
+            Some(instance) => Ok(*instance.get_instance().get_version()),
+            None => Err(TagError::RetrieveEmptyTag),
+        }
+    }
+    
+    pub fn get_parent(&self) -> Option<&str> {
+        self.instances.latest()?.parent_id.as_deref()
+    }
+    
+    pub fn get_color(&self) -> Result<Option<String>, TagError> {
+        match self.instances.latest() {
+            Some(instance) => Ok(instance.color.clone()),
+            None => Err(TagError::RetrieveEmptyTag),
+        }
+    }
 }
 
-#[derive(Debug)]
-pub enum TagError {
-    EditEmptyTag,
-    RetrieveEmptyTag,
-    Instance(InstanceError),
-}
-
-impl std::error::Error for TagError {}
-
-impl From<InstanceError> for TagError {
-    fn from(e: InstanceError) -> Self {
-        TagError::Instance(e)
+fn is_valid_color(color: &str) -> bool {
+    match color.strip_prefix('#') {
+        Some(hex) => hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
     }
 }
 
-impl std::fmt::Display for TagError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        match self {
-            TagError::EditEmptyTag => write!(f, "Cannot edit an empty tag"),
-            TagError::Instance(e) => write!(f, "Tag Instance Error: {}", e),
-            TagError::RetrieveEmptyTag => write!(f, "Cannot retrieve an empty tag"),
-        }
-    }
+#[derive(Debug, thiserror::Error)]
+pub enum TagError {
+    #[error("Cannot edit an empty tag")]
+    EditEmptyTag,
+    #[error("Cannot retrieve an empty tag")]
+    RetrieveEmptyTag,
+    #[error("Invalid tag color: {0}")]
+    InvalidColor(String),
+    #[error("Cannot merge a tag into itself")]
+    MergeWithSelf,
+    #[error("Cannot merge from deleted tag {0}")]
+    MergeFromDeleted(String),
+    #[error("Tag Instance Error: {0}")]
+    Instance(#[from] InstanceError),
 }
 
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct TagInstance {
     id: String,
     value: String,
-    instance: Instance
+    instance: Instance,
+    #[cfg_attr(feature = "serde", serde(default))]
+    parent_id: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    color: Option<String>,
 }
 
 impl TagInstance {
-    pub fn new(value: String) -> Self {
+    pub fn new_with(value: String, generator: &dyn IdGenerator) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
+            id: generator.generate(),
             value,
             instance: Instance::create_initial_instance(VersionLevel::Major),
+            parent_id: None,
+            color: None,
         }
     }
     
-    pub fn with_instance(value: String, instance: Instance) -> Self {
+    /// Creates the next instance in a tag's history, carrying over the previous attributes.
+    fn from_previous(previous: &TagInstance, instance: Instance) -> Self {
         Self {
-            id: Uuid::new_v4().to_string(),
-            value,
+            id: UuidGenerator.generate(),
+            value: previous.value.clone(),
             instance,
+            parent_id: previous.parent_id.clone(),
+            color: previous.color.clone(),
         }
     }
 }
@@ -118,10 +248,16 @@ impl Instanced for TagInstance {
     }
 }
 
+impl InstancedMut for TagInstance {
+    fn get_instance_mut(&mut self) -> &mut Instance {
+        &mut self.instance
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::version::Version;
+    use crate::instance::InstanceType;
     
     struct TestTag {
         tag: Tag,
@@ -152,4 +288,176 @@ mod tests {
         
         assert_eq!(tag.tag.get_value().unwrap(), "Test Tag 2");
     }
+    
+    #[test]
+    fn test_set_parent() -> Result<(), TagError> {
+        let animals = Tag::new(String::from("Animals"));
+        let mut cats = Tag::new(String::from("Cats"));
+        assert_eq!(cats.get_parent(), None);
+        
+        cats.set_parent(Some(animals.get_id().to_string()))?;
+        assert_eq!(cats.get_parent(), Some(animals.get_id()));
+        assert_eq!(cats.instances.latest().unwrap().get_instance().get_version(), &Version::new(1, 1, 0));
+        
+        cats.edit(String::from("Felines"), String::from("Rename"))?;
+        assert_eq!(cats.get_parent(), Some(animals.get_id()));
+        
+        cats.set_parent(None)?;
+        assert_eq!(cats.get_parent(), None);
+        assert_eq!(cats.instances.len(), 4);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_history() -> Result<(), TagError> {
+        let mut tag = Tag::new(String::from("Cats"));
+        tag.edit(String::from("Felines"), String::from("Rename"))?;
+        tag.edit(String::from("Felidae"), String::from("Rename again"))?;
+        
+        let history: Vec<(&Version, &str, &Zoned)> = tag.history().collect();
+        assert_eq!(history.len(), 3);
+        
+        let values: Vec<&str> = history.iter().map(|(_, value, _)| *value).collect();
+        assert_eq!(values, vec!["Cats", "Felines", "Felidae"]);
+        
+        let versions: Vec<&Version> = history.iter().map(|(version, _, _)| *version).collect();
+        assert_eq!(versions, vec![&Version::new(1, 0, 0), &Version::new(2, 0, 0), &Version::new(3, 0, 0)]);
+        
+        assert!(history[0].2 <= history[2].2);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_merge_from() -> Result<(), TagError> {
+        let mut other = Tag::new(String::from("Kittens"));
+        other.edit(String::from("Kitties"), String::from("Rename"))?;
+        
+        let mut tag = Tag::new(String::from("Cats"));
+        tag.set_color(String::from("#000000"), String::from("Black"))?;
+        let tag_id = tag.get_id().to_string();
+        
+        tag.merge_from(&other, String::from("Merged duplicate tag"))?;
+        
+        assert_eq!(tag.get_id(), tag_id);
+        assert_eq!(tag.get_value()?, "Cats");
+        assert_eq!(tag.get_color()?, Some(String::from("#000000")));
+        
+        let values: Vec<&str> = tag.instances.iter().map(|instance| instance.value.as_str()).collect();
+        assert_eq!(values, vec!["Kittens", "Kitties", "Cats", "Cats", "Cats"]);
+        
+        let latest = tag.instances.latest().unwrap().get_instance();
+        assert_eq!(latest.get_change_note(), "Merged duplicate tag");
+        assert!(tag.current_version()?.is_newer_than(&other.current_version()?));
+        
+        let versions: Vec<Version> = tag.instances.iter().map(|instance| *instance.get_instance().get_version()).collect();
+        assert!(versions.windows(2).all(|pair| pair[1].is_newer_than(&pair[0])));
+        
+        let creations = tag.instances.iter().filter(|instance| instance.get_instance().is_type_of(InstanceType::Creation)).count();
+        assert_eq!(creations, 1);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_merge_from_newer_tag() -> Result<(), TagError> {
+        let mut other = Tag::new(String::from("Kittens"));
+        for value in ["Kitties", "Kitty Cats", "Cats"] {
+            other.edit(String::from(value), String::from("Rename"))?;
+        }
+        
+        let mut tag = Tag::new(String::from("Cats"));
+        tag.merge_from(&other, String::from("Merged duplicate tag"))?;
+        
+        let values: Vec<&str> = tag.instances.iter().map(|instance| instance.value.as_str()).collect();
+        assert_eq!(values, vec!["Kittens", "Kitties", "Kitty Cats", "Cats", "Cats", "Cats"]);
+        assert!(tag.current_version()?.is_newer_than(&other.current_version()?));
+        
+        let creations = tag.instances.iter().filter(|instance| instance.get_instance().is_type_of(InstanceType::Creation)).count();
+        assert_eq!(creations, 1);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_merge_from_rejects_self_and_deleted() -> Result<(), TagError> {
+        let mut tag = Tag::new(String::from("Cats"));
+        let copy = tag.clone();
+        assert!(matches!(tag.merge_from(&copy, String::from("Merge")), Err(TagError::MergeWithSelf)));
+        
+        let mut other = Tag::new(String::from("Kittens"));
+        other.delete(None)?;
+        assert!(matches!(tag.merge_from(&other, String::from("Merge")), Err(TagError::MergeFromDeleted(_))));
+        assert_eq!(tag.instances.len(), 1);
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_value_matches() {
+        let tag = Tag::new(String::from("CATS"));
+        assert!(tag.value_matches("cats"));
+        assert!(tag.value_matches("  Cats "));
+        assert!(!tag.value_matches("cat"));
+        assert!(!tag.value_matches("dogs"));
+        
+        let tag = Tag::new(String::from("ÉCOLE"));
+        assert!(tag.value_matches("école"));
+    }
+    
+    #[test]
+    fn test_set_color() -> Result<(), TagError> {
+        let mut tag = Tag::new(String::from("Cats"));
+        assert_eq!(tag.get_color()?, None);
+        
+        tag.set_color(String::from("#1a2B3c"), String::from("Color for the sidebar"))?;
+        assert_eq!(tag.get_color()?, Some(String::from("#1a2B3c")));
+        
+        tag.edit(String::from("Felines"), String::from("Rename"))?;
+        assert_eq!(tag.get_color()?, Some(String::from("#1a2B3c")));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_set_invalid_color() {
+        let mut tag = Tag::new(String::from("Cats"));
+        
+        for color in ["1a2b3c", "#1a2b3", "#1a2b3c4", "#gggggg", ""] {
+            let result = tag.set_color(String::from(color), String::from("Bad color"));
+            assert!(matches!(result, Err(TagError::InvalidColor(_))), "{:?} should be rejected", color);
+        }
+        
+        assert_eq!(tag.instances.len(), 1);
+        assert_eq!(tag.get_color().unwrap(), None);
+    }
+    
+    #[test]
+    fn test_tag_delete_and_restore() -> Result<(), TagError> {
+        let mut tag = Tag::new(String::from("Cats"));
+        assert!(!tag.is_deleted());
+        assert_eq!(tag.current_version()?, Version::new(1, 0, 0));
+        
+        tag.delete(None)?;
+        assert!(tag.is_deleted());
+        let deleted_version = tag.current_version()?;
+        assert!(deleted_version.is_newer_than(&Version::new(1, 0, 0)));
+        
+        tag.restore(None)?;
+        assert!(!tag.is_deleted());
+        assert!(tag.current_version()?.is_newer_than(&deleted_version));
+        
+        Ok(())
+    }
+    
+    #[test]
+    fn test_tag_new_with_sequential_ids() {
+        let generator = crate::id::SequentialGenerator::new();
+        
+        let tag = Tag::new_with(String::from("Cats"), &generator);
+        assert_eq!(tag.get_id(), "1");
+        assert_eq!(tag.instances.latest().unwrap().id, "2");
+        assert_eq!(tag.get_value().unwrap(), "Cats");
+    }
 }
\ No newline at end of file
@@ -1,13 +1,52 @@
+use std::cmp::Ordering;
 use std::fmt::{Debug, Display};
 use std::num::ParseIntError;
+use std::ops::{Bound, RangeBounds};
+use std::str::FromStr;
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+/// The longest pre-release identifier a version can carry. It is stored inline so that
+/// `Version` stays `Copy`.
+pub const MAX_PRE_RELEASE_LENGTH: usize = 32;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Hash)]
 pub struct Version {
     major: u16,
     minor: u16,
     patch: u16,
+    pre_release: Option<PreRelease>,
+}
+
+/// A validated pre-release identifier, stored as zero-padded ASCII.
+#[derive(Eq, PartialEq, Clone, Copy, Hash)]
+struct PreRelease {
+    bytes: [u8; MAX_PRE_RELEASE_LENGTH],
+    len: u8,
+}
+
+impl PreRelease {
+    /// Callers must check the identifier with `is_valid_pre_release` first.
+    fn new(pre_release: &str) -> Self {
+        let mut bytes = [0; MAX_PRE_RELEASE_LENGTH];
+        bytes[..pre_release.len()].copy_from_slice(pre_release.as_bytes());
+        
+        Self {
+            bytes,
+            len: pre_release.len() as u8,
+        }
+    }
+    
+    fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes[..self.len as usize]).unwrap_or_default()
+    }
 }
 
+impl Debug for PreRelease {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum VersionLevel {
     Major,
     Minor,
@@ -15,38 +54,86 @@ pub enum VersionLevel {
 }
 
 impl Version {
+    /// The `0.0.0` version that initial versions are derived from.
+    pub const ZERO: Version = Version {
+        major: 0,
+        minor: 0,
+        patch: 0,
+        pre_release: None,
+    };
+    
     pub fn from_string(version: &str) -> Result<Version, VersionError> {
-        let mut parts: Vec<&str> = version.split('.').collect();
-
-        if parts.len() != 3 {
-            parts = version.split('-').collect();
-            
-            if parts.len() != 3 {
-                return Err(VersionError::InvalidVersionString(version.to_string()));
-            }
-        }
-
-        Ok(Version {
-            major: parts[0].parse()?,
-            minor: parts[1].parse()?,
-            patch: parts[2].parse()?,
-        })
+        version.parse()
     }
+    
     pub fn new(major: u16, minor: u16, patch: u16) -> Version {
         Version {
             major,
             minor,
             patch,
+            pre_release: None,
         }
     }
     
+    pub fn with_pre_release(mut self, pre_release: &str) -> Result<Version, VersionError> {
+        if !is_valid_pre_release(pre_release) {
+            return Err(VersionError::InvalidVersionString(format!("{}-{}", self, pre_release)));
+        }
+        
+        self.pre_release = Some(PreRelease::new(pre_release));
+        Ok(self)
+    }
+    
+    pub fn get_pre_release(&self) -> Option<&str> {
+        self.pre_release.as_ref().map(PreRelease::as_str)
+    }
+    
+    /// Whether this is `0.0.0`, i.e. `Version::ZERO`.
+    pub fn is_initial(&self) -> bool {
+        *self == Version::ZERO
+    }
+    
+    pub fn from_parts(parts: (u16, u16, u16)) -> Version {
+        Version::new(parts.0, parts.1, parts.2)
+    }
+    
+    /// The major, minor and patch numbers. Any pre-release identifier is dropped.
+    pub fn into_parts(self) -> (u16, u16, u16) {
+        (self.major, self.minor, self.patch)
+    }
+    
+    /// Packs the version into a single integer with the major number in the high bits, so
+    /// integers sort the same way as release versions. Pre-release identifiers are ignored.
+    pub fn as_u64(&self) -> u64 {
+        ((self.major as u64) << 32) | ((self.minor as u64) << 16) | self.patch as u64
+    }
+    
     pub fn create_child_version(&self, change: VersionLevel) -> Version {
-        let mut version = self.clone();
+        let mut version = *self;
         version.increment(change);
         version
     }
+    
+    /// Applies the levels in order, returning each resulting version (not including `self`).
+    pub fn apply_all(&self, levels: &[VersionLevel]) -> Vec<Version> {
+        let mut version = *self;
+        levels.iter().map(|level| {
+            version.increment(*level);
+            version
+        }).collect()
+    }
+    
+    pub fn try_create_child_version(&self, change: VersionLevel) -> Result<Version, VersionError> {
+        let mut version = *self;
+        version.checked_increment(change)?;
+        Ok(version)
+    }
 
+    /// Increments the given level. The result is always a release version, so any
+    /// pre-release identifier is dropped.
     pub fn increment(&mut self, change: VersionLevel) {
+        self.pre_release = None;
+        
         match change {
             VersionLevel::Major => {
                 self.major += 1;
@@ -60,37 +147,338 @@ impl Version {
             VersionLevel::Patch => self.patch += 1,
         }
     }
+    
+    /// Like `increment`, but returns `VersionError::Overflow` instead of overflowing a
+    /// component. The version is left untouched on error.
+    pub fn checked_increment(&mut self, change: VersionLevel) -> Result<(), VersionError> {
+        let incremented = match change {
+            VersionLevel::Major => Version::new(self.major.checked_add(1).ok_or(VersionError::Overflow)?, 0, 0),
+            VersionLevel::Minor => Version::new(self.major, self.minor.checked_add(1).ok_or(VersionError::Overflow)?, 0),
+            VersionLevel::Patch => Version::new(self.major, self.minor, self.patch.checked_add(1).ok_or(VersionError::Overflow)?),
+        };
+        
+        *self = incremented;
+        Ok(())
+    }
 
-    pub fn to_string(&self) -> String {
-        format!("{}.{}.{}", self.major, self.minor, self.patch)
+    pub fn is_newer_than(&self, other: &Version) -> bool {
+        self > other
+    }
+    
+    pub fn is_older_than(&self, other: &Version) -> bool {
+        self < other
+    }
+    
+    /// Returns the most significant level that differs between the two versions, or `None`
+    /// when they are equal. A difference in pre-release alone is reported as a patch change.
+    pub fn diff(&self, other: &Version) -> Option<VersionLevel> {
+        if self.major != other.major {
+            Some(VersionLevel::Major)
+        } else if self.minor != other.minor {
+            Some(VersionLevel::Minor)
+        } else if self.patch != other.patch || self.pre_release != other.pre_release {
+            Some(VersionLevel::Patch)
+        } else {
+            None
+        }
     }
     
     pub fn file_safe_string(&self) -> String {
-        format!("{}-{}-{}", self.major, self.minor, self.patch)
+        match self.get_pre_release() {
+            Some(pre_release) => format!("{}-{}-{}-{}", self.major, self.minor, self.patch, pre_release),
+            None => format!("{}-{}-{}", self.major, self.minor, self.patch),
+        }
     }
 }
 
-#[derive(Debug)]
-pub enum VersionError {
-    InvalidVersionString(String),
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        
+        if let Some(pre_release) = self.get_pre_release() {
+            write!(f, "-{}", pre_release)?;
+        }
+        
+        Ok(())
+    }
 }
 
-impl From<ParseIntError> for VersionError {
-    fn from(e: ParseIntError) -> VersionError {
-        VersionError::InvalidVersionString(e.to_string())
+impl Ord for Version {
+    /// Orders by major, minor and patch, then by pre-release following semver precedence:
+    /// a pre-release is lower than the same version without one.
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| compare_pre_release(self.get_pre_release(), other.get_pre_release()))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn compare_pre_release(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => {
+            for (a_identifier, b_identifier) in a.split('.').zip(b.split('.')) {
+                let ordering = compare_pre_release_identifier(a_identifier, b_identifier);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            
+            a.split('.').count().cmp(&b.split('.').count())
+        }
     }
 }
 
-impl std::error::Error for VersionError {}
+fn compare_pre_release_identifier(a: &str, b: &str) -> Ordering {
+    let a_numeric = a.bytes().all(|c| c.is_ascii_digit());
+    let b_numeric = b.bytes().all(|c| c.is_ascii_digit());
+    
+    match (a_numeric, b_numeric) {
+        // Numeric identifiers have no leading zeros, so the longer one is the larger.
+        (true, true) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.cmp(b),
+    }
+}
+
+fn is_valid_pre_release(pre_release: &str) -> bool {
+    pre_release.len() <= MAX_PRE_RELEASE_LENGTH && pre_release.split('.').all(|identifier| {
+        let numeric = identifier.bytes().all(|c| c.is_ascii_digit());
+        
+        !identifier.is_empty()
+            && identifier.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'-')
+            && !(numeric && identifier.len() > 1 && identifier.starts_with('0'))
+    })
+}
+
+impl FromStr for Version {
+    type Err = VersionError;
+
+    /// Accepts `1.2.3` and `1.2.3-alpha.1`, as well as the file safe forms `1-2-3` and
+    /// `1-2-3-alpha.1`. A single leading `v` or `V` is ignored.
+    fn from_str(version: &str) -> Result<Self, Self::Err> {
+        let unprefixed = version.strip_prefix(['v', 'V']).unwrap_or(version);
+        
+        let (core, mut pre_release) = match unprefixed.split_once('-') {
+            Some((core, pre_release)) if core.split('.').count() == 3 => (core, Some(pre_release)),
+            _ => (unprefixed, None),
+        };
+        
+        let mut parts: Vec<&str> = core.split('.').collect();
+
+        if parts.len() != 3 {
+            parts = unprefixed.splitn(4, '-').collect();
+            
+            if parts.len() < 3 {
+                return Err(VersionError::InvalidVersionString(version.to_string()));
+            }
+            
+            pre_release = parts.get(3).copied();
+        }
+        
+        if let Some(pre_release) = pre_release {
+            if !is_valid_pre_release(pre_release) {
+                return Err(VersionError::InvalidVersionString(version.to_string()));
+            }
+        }
+
+        Ok(Version {
+            major: parts[0].parse()?,
+            minor: parts[1].parse()?,
+            patch: parts[2].parse()?,
+            pre_release: pre_release.map(PreRelease::new),
+        })
+    }
+}
 
-impl Display for VersionError {
+impl VersionLevel {
+    /// The most significant level in `levels`, or `None` if it's empty.
+    pub fn max_of(levels: &[VersionLevel]) -> Option<VersionLevel> {
+        levels.iter().copied().max()
+    }
+    
+    fn significance(&self) -> u8 {
+        match self {
+            VersionLevel::Major => 2,
+            VersionLevel::Minor => 1,
+            VersionLevel::Patch => 0,
+        }
+    }
+}
+
+/// Levels are ordered by significance, so `Major` is the greatest.
+impl Ord for VersionLevel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.significance().cmp(&other.significance())
+    }
+}
+
+impl PartialOrd for VersionLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for VersionLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
-            VersionError::InvalidVersionString(version) => write!(f, "Invalid version string: {}", version),
+            VersionLevel::Major => write!(f, "major"),
+            VersionLevel::Minor => write!(f, "minor"),
+            VersionLevel::Patch => write!(f, "patch"),
+        }
+    }
+}
+
+impl FromStr for VersionLevel {
+    type Err = VersionError;
+    
+    /// Parses "major", "minor" or "patch", ignoring case.
+    fn from_str(level: &str) -> Result<Self, Self::Err> {
+        match level.to_lowercase().as_str() {
+            "major" => Ok(VersionLevel::Major),
+            "minor" => Ok(VersionLevel::Minor),
+            "patch" => Ok(VersionLevel::Patch),
+            _ => Err(VersionError::InvalidLevel(level.to_string())),
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    lower: Bound<Version>,
+    upper: Bound<Version>,
+}
+
+impl VersionRange {
+    pub fn new(lower: Bound<Version>, upper: Bound<Version>) -> Self {
+        Self {
+            lower,
+            upper,
+        }
+    }
+    
+    pub fn between(min: Version, max: Version) -> Self {
+        Self::new(Bound::Included(min), Bound::Included(max))
+    }
+    
+    pub fn at_least(min: Version) -> Self {
+        Self::new(Bound::Included(min), Bound::Unbounded)
+    }
+    
+    /// Parses a range expression. Supported forms are `*`, `>=1.2.0`, `>1.2.0`, `<=1.2.0`,
+    /// `<1.2.0`, `=1.2.0` or a bare `1.2.0`, the wildcards `1.*` and `1.2.*`, and the
+    /// ranges `1.2.0..2.0.0` (upper bound excluded) and `1.2.0..=2.0.0` (upper bound included).
+    pub fn parse(range: &str) -> Result<Self, VersionError> {
+        let range = range.trim();
+        let parse_version = |version: &str| {
+            Version::from_string(version.trim()).map_err(|_| VersionError::InvalidRange(range.to_string()))
+        };
+        
+        if range == "*" {
+            return Ok(Self::new(Bound::Unbounded, Bound::Unbounded));
+        }
+        
+        if let Some((min, max)) = range.split_once("..=") {
+            return Ok(Self::new(Bound::Included(parse_version(min)?), Bound::Included(parse_version(max)?)));
+        }
+        
+        if let Some((min, max)) = range.split_once("..") {
+            return Ok(Self::new(Bound::Included(parse_version(min)?), Bound::Excluded(parse_version(max)?)));
+        }
+        
+        if let Some(prefix) = range.strip_suffix(".*") {
+            return Self::parse_wildcard(prefix).ok_or_else(|| VersionError::InvalidRange(range.to_string()));
+        }
+        
+        if let Some(min) = range.strip_prefix(">=") {
+            Ok(Self::new(Bound::Included(parse_version(min)?), Bound::Unbounded))
+        } else if let Some(min) = range.strip_prefix('>') {
+            Ok(Self::new(Bound::Excluded(parse_version(min)?), Bound::Unbounded))
+        } else if let Some(max) = range.strip_prefix("<=") {
+            Ok(Self::new(Bound::Unbounded, Bound::Included(parse_version(max)?)))
+        } else if let Some(max) = range.strip_prefix('<') {
+            Ok(Self::new(Bound::Unbounded, Bound::Excluded(parse_version(max)?)))
+        } else {
+            let version = parse_version(range.strip_prefix('=').unwrap_or(range))?;
+            Ok(Self::between(version, version))
+        }
+    }
+    
+    fn parse_wildcard(prefix: &str) -> Option<Self> {
+        let parts = prefix
+            .split('.')
+            .map(|part| part.parse::<u16>().ok())
+            .collect::<Option<Vec<u16>>>()?;
+        
+        let (lower, level) = match parts.as_slice() {
+            [major] => (Version::new(*major, 0, 0), VersionLevel::Major),
+            [major, minor] => (Version::new(*major, *minor, 0), VersionLevel::Minor),
+            _ => return None,
+        };
+        
+        let upper = match lower.try_create_child_version(level) {
+            Ok(upper) => Bound::Excluded(upper),
+            Err(_) => Bound::Unbounded,
+        };
+        
+        Some(Self::new(Bound::Included(lower), upper))
+    }
+    
+    pub fn matches(&self, version: &Version) -> bool {
+        (self.lower.as_ref(), self.upper.as_ref()).contains(version)
+    }
+}
+
+impl FromStr for VersionRange {
+    type Err = VersionError;
+
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        VersionRange::parse(range)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Version {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Version {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = String::deserialize(deserializer)?;
+        Version::from_string(&version).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VersionError {
+    #[error("Invalid version string: {0}")]
+    InvalidVersionString(String),
+    #[error("Invalid version range: {0}")]
+    InvalidRange(String),
+    #[error("Invalid version level: {0}")]
+    InvalidLevel(String),
+    #[error("Version component overflowed")]
+    Overflow,
+}
+
+impl From<ParseIntError> for VersionError {
+    fn from(e: ParseIntError) -> VersionError {
+        VersionError::InvalidVersionString(e.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,6 +491,97 @@ mod tests {
         assert_eq!(version.patch, 3);
     }
     
+    #[test]
+    fn test_version_parse() {
+        let version: Version = "1.2.3".parse().unwrap();
+        assert_eq!(version, Version::new(1, 2, 3));
+
+        let version: Version = "1-2-3".parse().unwrap();
+        assert_eq!(version, Version::new(1, 2, 3));
+
+        assert!("1.2".parse::<Version>().is_err());
+    }
+
+    #[test]
+    fn test_version_parse_iterator() {
+        let versions: Result<Vec<Version>, VersionError> = "1.0.0\n1.1.0\n2.0.0".lines().map(str::parse).collect();
+        assert_eq!(versions.unwrap(), vec![Version::new(1, 0, 0), Version::new(1, 1, 0), Version::new(2, 0, 0)]);
+    }
+
+    #[test]
+    fn test_version_zero() {
+        assert_eq!(Version::ZERO, Version::new(0, 0, 0));
+        assert!(Version::ZERO.is_initial());
+        assert!(Version::new(0, 0, 0).is_initial());
+        assert!(!Version::new(1, 0, 0).is_initial());
+        assert!(!Version::new(0, 0, 0).with_pre_release("alpha").unwrap().is_initial());
+    }
+    
+    #[test]
+    fn test_version_hash() {
+        let versions: std::collections::HashSet<Version> = [
+            Version::new(1, 2, 3),
+            Version::from_string("1.2.3").unwrap(),
+            Version::from_string("1-2-3").unwrap(),
+            Version::new(1, 2, 4),
+            Version::new(3, 2, 1),
+            Version::new(1, 2, 3).with_pre_release("alpha").unwrap(),
+        ].into_iter().collect();
+        
+        assert_eq!(versions.len(), 4);
+        assert!(versions.contains(&Version::new(1, 2, 3)));
+        assert!(versions.contains(&Version::from_string("1.2.3-alpha").unwrap()));
+        assert!(!versions.contains(&Version::new(2, 1, 3)));
+    }
+    
+    #[test]
+    fn test_version_pre_release_round_trip() {
+        let version = Version::from_string("1.2.3-alpha.1").unwrap();
+        assert_eq!(version.get_pre_release(), Some("alpha.1"));
+        assert_eq!(version.to_string(), "1.2.3-alpha.1");
+        assert_eq!(version.file_safe_string(), "1-2-3-alpha.1");
+        
+        assert_eq!(Version::from_string(&version.to_string()).unwrap(), version);
+        assert_eq!(Version::from_string(&version.file_safe_string()).unwrap(), version);
+        assert_eq!(Version::new(1, 2, 3).with_pre_release("alpha.1").unwrap(), version);
+    }
+    
+    #[test]
+    fn test_version_pre_release_invalid() {
+        assert!(Version::from_string("1.2.3-").is_err());
+        assert!(Version::from_string("1.2.3-alpha..1").is_err());
+        assert!(Version::from_string("1.2.3-alpha.01").is_err());
+        assert!(Version::from_string("1.2.3-alpha_1").is_err());
+        assert!(Version::new(1, 2, 3).with_pre_release("").is_err());
+        
+        let longest = "a".repeat(MAX_PRE_RELEASE_LENGTH);
+        assert_eq!(Version::new(1, 2, 3).with_pre_release(&longest).unwrap().get_pre_release(), Some(longest.as_str()));
+        assert!(Version::new(1, 2, 3).with_pre_release(&format!("{}a", longest)).is_err());
+        assert!(Version::from_string(&format!("1.2.3-{}a", longest)).is_err());
+    }
+    
+    #[test]
+    fn test_version_pre_release_ordering() {
+        let release = Version::new(1, 2, 3);
+        let versions: Vec<Version> = ["1.2.3-alpha", "1.2.3-alpha.1", "1.2.3-alpha.beta", "1.2.3-beta.2", "1.2.3-beta.11", "1.2.3-rc.1"]
+            .iter()
+            .map(|version| Version::from_string(version).unwrap())
+            .collect();
+        
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1], "{} should be lower than {}", pair[0], pair[1]);
+        }
+        
+        assert!(versions.iter().all(|version| version < &release));
+        assert!(Version::from_string("1.2.2").unwrap() < versions[0]);
+    }
+    
+    #[test]
+    fn test_version_increment_drops_pre_release() {
+        let version = Version::from_string("1.2.3-rc.1").unwrap();
+        assert_eq!(version.create_child_version(VersionLevel::Patch), Version::new(1, 2, 4));
+    }
+
     #[test]
     fn test_version_create_child_version() {
         let version = Version::new(1, 2, 3);
@@ -128,6 +607,12 @@ mod tests {
         assert_eq!(version.to_string(), "1.2.3");
     }
 
+    #[test]
+    fn test_version_display() {
+        let version = Version::new(1, 2, 3);
+        assert_eq!(format!("{version}"), "1.2.3");
+    }
+
     #[test]
     fn test_version_increment() {
         let mut version = Version::new(1, 2, 3);
@@ -147,6 +632,159 @@ mod tests {
         assert_eq!(version.patch, 1);
     }
 
+    #[test]
+    fn test_version_checked_increment() {
+        let mut version = Version::new(1, 2, 3);
+        version.checked_increment(VersionLevel::Minor).unwrap();
+        assert_eq!(version, Version::new(1, 3, 0));
+        
+        let mut version = Version::new(u16::MAX, 2, 3);
+        assert!(matches!(version.checked_increment(VersionLevel::Major), Err(VersionError::Overflow)));
+        assert_eq!(version, Version::new(u16::MAX, 2, 3));
+        
+        let mut version = Version::new(1, u16::MAX, 3);
+        assert!(matches!(version.checked_increment(VersionLevel::Minor), Err(VersionError::Overflow)));
+        
+        let mut version = Version::new(1, 2, u16::MAX);
+        assert!(matches!(version.checked_increment(VersionLevel::Patch), Err(VersionError::Overflow)));
+        version.checked_increment(VersionLevel::Minor).unwrap();
+        assert_eq!(version, Version::new(1, 3, 0));
+    }
+    
+    #[test]
+    fn test_version_try_create_child_version() {
+        let version = Version::new(1, 2, u16::MAX);
+        assert!(matches!(version.try_create_child_version(VersionLevel::Patch), Err(VersionError::Overflow)));
+        assert_eq!(version.try_create_child_version(VersionLevel::Major).unwrap(), Version::new(2, 0, 0));
+    }
+
+    #[test]
+    fn test_version_diff() {
+        let version = Version::new(1, 0, 0);
+        assert!(matches!(version.diff(&Version::new(2, 0, 0)), Some(VersionLevel::Major)));
+        assert!(matches!(version.diff(&Version::new(1, 1, 0)), Some(VersionLevel::Minor)));
+        assert!(matches!(version.diff(&Version::new(1, 0, 1)), Some(VersionLevel::Patch)));
+        assert!(matches!(Version::new(2, 1, 1).diff(&version), Some(VersionLevel::Major)));
+        assert!(matches!(version.diff(&Version::from_string("1.0.0-rc.1").unwrap()), Some(VersionLevel::Patch)));
+        assert!(version.diff(&Version::new(1, 0, 0)).is_none());
+    }
+
+    #[test]
+    fn test_version_from_string_leading_v() {
+        assert_eq!(Version::from_string("v1.2.3").unwrap(), Version::new(1, 2, 3));
+        assert_eq!(Version::from_string("V1.2.3").unwrap(), Version::new(1, 2, 3));
+        assert_eq!(Version::from_string("v1-2-3").unwrap(), Version::from_string("1.2.3").unwrap());
+        assert_eq!(Version::from_string("v1.2.3-rc.1").unwrap(), Version::from_string("1.2.3-rc.1").unwrap());
+    }
+    
+    #[test]
+    fn test_version_from_string_leading_v_rejected() {
+        assert!(matches!(Version::from_string("version1.2.3"), Err(VersionError::InvalidVersionString(_))));
+        assert!(matches!(Version::from_string("vv1.2.3"), Err(VersionError::InvalidVersionString(_))));
+        assert!(matches!(Version::from_string("v"), Err(VersionError::InvalidVersionString(_))));
+        assert!(matches!(Version::from_string("1.2.3v"), Err(VersionError::InvalidVersionString(_))));
+    }
+
+    #[test]
+    fn test_version_is_newer_and_older_than() {
+        let version = Version::new(1, 2, 3);
+        let others = [
+            Version::new(0, 9, 9),
+            Version::new(2, 0, 0),
+            Version::new(1, 1, 9),
+            Version::new(1, 3, 0),
+            Version::new(1, 2, 2),
+            Version::new(1, 2, 4),
+            Version::new(1, 2, 3),
+        ];
+        
+        for other in &others {
+            assert_eq!(version.is_newer_than(other), version > *other);
+            assert_eq!(version.is_older_than(other), version < *other);
+        }
+        
+        assert!(Version::new(2, 0, 0).is_newer_than(&version));
+        assert!(Version::new(1, 2, 4).is_newer_than(&version));
+        assert!(Version::new(1, 1, 9).is_older_than(&version));
+        assert!(!version.is_newer_than(&version));
+        assert!(!version.is_older_than(&version));
+    }
+
+    #[test]
+    fn test_version_range_lower_bound() {
+        let range = VersionRange::parse(">=1.2.0").unwrap();
+        assert!(range.matches(&Version::new(1, 2, 0)));
+        assert!(range.matches(&Version::new(5, 0, 0)));
+        assert!(!range.matches(&Version::new(1, 1, 9)));
+        
+        let range = VersionRange::parse(">1.2.0").unwrap();
+        assert!(!range.matches(&Version::new(1, 2, 0)));
+        assert!(range.matches(&Version::new(1, 2, 1)));
+        
+        assert_eq!(VersionRange::parse(">=1.2.0").unwrap(), VersionRange::at_least(Version::new(1, 2, 0)));
+    }
+    
+    #[test]
+    fn test_version_range_upper_bound() {
+        let range = VersionRange::parse("<2.0.0").unwrap();
+        assert!(range.matches(&Version::new(1, 9, 9)));
+        assert!(!range.matches(&Version::new(2, 0, 0)));
+        
+        let range = VersionRange::parse("<=2.0.0").unwrap();
+        assert!(range.matches(&Version::new(2, 0, 0)));
+        assert!(!range.matches(&Version::new(2, 0, 1)));
+    }
+    
+    #[test]
+    fn test_version_range_between() {
+        let range = VersionRange::parse("1.2.0..2.0.0").unwrap();
+        assert!(range.matches(&Version::new(1, 2, 0)));
+        assert!(range.matches(&Version::new(1, 9, 9)));
+        assert!(!range.matches(&Version::new(2, 0, 0)));
+        assert!(!range.matches(&Version::new(1, 1, 9)));
+        
+        let range = VersionRange::parse("1.2.0..=2.0.0").unwrap();
+        assert!(range.matches(&Version::new(2, 0, 0)));
+        assert!(!range.matches(&Version::new(2, 0, 1)));
+        assert_eq!(range, VersionRange::between(Version::new(1, 2, 0), Version::new(2, 0, 0)));
+    }
+    
+    #[test]
+    fn test_version_range_wildcard() {
+        let range = VersionRange::parse("1.*").unwrap();
+        assert!(range.matches(&Version::new(1, 0, 0)));
+        assert!(range.matches(&Version::new(1, 9, 9)));
+        assert!(!range.matches(&Version::new(2, 0, 0)));
+        assert!(!range.matches(&Version::new(0, 9, 9)));
+        
+        let range = VersionRange::parse("1.2.*").unwrap();
+        assert!(range.matches(&Version::new(1, 2, 0)));
+        assert!(range.matches(&Version::new(1, 2, 9)));
+        assert!(!range.matches(&Version::new(1, 3, 0)));
+        
+        let range = VersionRange::parse(&format!("{}.*", u16::MAX)).unwrap();
+        assert!(range.matches(&Version::new(u16::MAX, u16::MAX, u16::MAX)));
+        
+        assert!(VersionRange::parse("*").unwrap().matches(&Version::new(0, 0, 1)));
+    }
+    
+    #[test]
+    fn test_version_range_exact() {
+        let range: VersionRange = "=1.2.3".parse().unwrap();
+        assert!(range.matches(&Version::new(1, 2, 3)));
+        assert!(!range.matches(&Version::new(1, 2, 4)));
+        assert_eq!(range, "1.2.3".parse().unwrap());
+    }
+    
+    #[test]
+    fn test_version_range_invalid() {
+        for range in ["", ">=1.2", "1.2.3.*", "a.*", "1.2.0..", "..2.0.0", ">=abc"] {
+            assert!(matches!(VersionRange::parse(range), Err(VersionError::InvalidRange(_))), "{range} should be rejected");
+        }
+        
+        assert_eq!(VersionRange::parse(">=1.2").unwrap_err().to_string(), "Invalid version range: >=1.2");
+    }
+
     #[test]
     fn test_version_from_string_error() {
         let version = Version::from_string("1.2");
@@ -197,9 +835,120 @@ mod tests {
         assert_eq!(version2.major, 2);
     }
     
+    #[test]
+    fn test_version_level_traits() {
+        let level = VersionLevel::Minor;
+        let copy = level;
+        assert_eq!(level, copy);
+        assert_ne!(level, VersionLevel::Patch);
+        assert!(matches!(copy, VersionLevel::Minor));
+        assert_eq!(format!("{:?}", level), "Minor");
+        
+        let levels: std::collections::HashSet<VersionLevel> = [level, copy, VersionLevel::Major].into_iter().collect();
+        assert_eq!(levels.len(), 2);
+    }
+    
+    #[test]
+    fn test_version_level_ordering() {
+        assert!(VersionLevel::Major > VersionLevel::Minor);
+        assert!(VersionLevel::Minor > VersionLevel::Patch);
+        assert!(VersionLevel::Patch < VersionLevel::Major);
+        
+        assert_eq!(VersionLevel::max_of(&[VersionLevel::Patch, VersionLevel::Major, VersionLevel::Minor]), Some(VersionLevel::Major));
+        assert_eq!(VersionLevel::max_of(&[VersionLevel::Patch, VersionLevel::Minor, VersionLevel::Patch]), Some(VersionLevel::Minor));
+        assert_eq!(VersionLevel::max_of(&[]), None);
+    }
+    
+    #[test]
+    fn test_version_level_display_and_parse() {
+        let levels = [
+            (VersionLevel::Major, "major"),
+            (VersionLevel::Minor, "minor"),
+            (VersionLevel::Patch, "patch"),
+        ];
+        
+        for (level, display) in levels {
+            assert_eq!(level.to_string(), display);
+            assert_eq!(display.parse::<VersionLevel>().unwrap(), level);
+            assert_eq!(display.to_uppercase().parse::<VersionLevel>().unwrap(), level);
+        }
+    }
+    
+    #[test]
+    fn test_version_level_parse_invalid() {
+        for level in ["", "majorly", "1", "build"] {
+            let result = level.parse::<VersionLevel>();
+            assert!(matches!(result, Err(VersionError::InvalidLevel(_))), "{:?} should be rejected", level);
+        }
+    }
+    
+    #[test]
+    fn test_apply_all() {
+        let versions = Version::new(0, 0, 0).apply_all(&[VersionLevel::Minor, VersionLevel::Patch, VersionLevel::Patch, VersionLevel::Major]);
+        assert_eq!(versions, vec![
+            Version::new(0, 1, 0),
+            Version::new(0, 1, 1),
+            Version::new(0, 1, 2),
+            Version::new(1, 0, 0),
+        ]);
+        
+        assert!(Version::new(1, 0, 0).apply_all(&[]).is_empty());
+    }
+    
+    #[test]
+    fn test_parts() {
+        let version = Version::from_parts((1, 2, 3));
+        assert_eq!(version, Version::new(1, 2, 3));
+        assert_eq!(version.into_parts(), (1, 2, 3));
+        
+        let version = Version::new(1, 2, 3).with_pre_release("beta").unwrap();
+        assert_eq!(version.into_parts(), (1, 2, 3));
+    }
+    
+    #[test]
+    fn test_as_u64_ordering() {
+        let versions = [
+            Version::new(0, 0, 1),
+            Version::new(0, 1, 0),
+            Version::new(0, 1, 65535),
+            Version::new(0, 2, 0),
+            Version::new(1, 0, 0),
+            Version::new(1, 65535, 65535),
+            Version::new(65535, 0, 0),
+        ];
+        
+        for pair in versions.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].as_u64() < pair[1].as_u64());
+        }
+        
+        assert_eq!(Version::new(1, 2, 3).as_u64(), (1 << 32) + (2 << 16) + 3);
+    }
+    
     #[test]
     fn test_debug() {
         let version = Version::new(1, 2, 3);
-        assert_eq!(format!("{:?}", version), "Version { major: 1, minor: 2, patch: 3 }");
+        assert_eq!(format!("{:?}", version), "Version { major: 1, minor: 2, patch: 3, pre_release: None }");
+        
+        let version = version.with_pre_release("alpha.1").unwrap();
+        assert_eq!(format!("{:?}", version), "Version { major: 1, minor: 2, patch: 3, pre_release: Some(\"alpha.1\") }");
+    }
+    
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let version = Version::new(1, 2, 3);
+        let json = serde_json::to_string(&version).unwrap();
+        assert_eq!(json, "\"1.2.3\"");
+        
+        let deserialized: Version = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, version);
+    }
+    
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_invalid_string() {
+        let result = serde_json::from_str::<Version>("\"1.2\"");
+        assert!(result.is_err());
     }
 }
\ No newline at end of file